@@ -58,7 +58,10 @@ delegate() {
   sender=$1
   growth_amount=$2
   profit_amount=$3
-  msg='{"delegate":{"growth":"'$growth_amount'","profit":"'$profit_amount'"}}'
+  amount=$(($growth_amount + $profit_amount))
+  hook_msg=$(echo -n '{"delegate":{"growth":"'$growth_amount'","profit":"'$profit_amount'"}}' | base64 -w 0)
+  msg='{"send":{"contract":"'$CONTRACT_ADDR'","amount":"'$amount'","msg":"'$hook_msg'"}}'
+  cw20_addr=juno1j0a9ymgngasfn3l5me8qpd53l5zlm9wurfdk7r65s5mg6tkxal3qpgf5se
   flags="\
   --node $NODE \
   --gas-prices 0.025$DENOM \
@@ -70,9 +73,8 @@ delegate() {
   --output json \
   -y \
   "
-  echo junod tx wasm execute $CONTRACT_ADDR "$msg" "$flags"
-  junod tx wasm execute juno1j0a9ymgngasfn3l5me8qpd53l5zlm9wurfdk7r65s5mg6tkxal3qpgf5se '{"increase_allowance":{"spender":"'$CONTRACT_ADDR'","amount":"'$growth_amount'"}}' $flags
-  response=$(junod tx wasm execute "$CONTRACT_ADDR" "$msg" $flags)
+  echo junod tx wasm execute $cw20_addr "$msg" "$flags"
+  response=$(junod tx wasm execute "$cw20_addr" "$msg" $flags)
   echo $response | ./bin/utils/base64-decode-attributes | jq
 }
 
@@ -1,6 +1,7 @@
 use cosmwasm_std::{to_binary, Addr, Coin, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
 
-use crate::msg::ExecuteMsg;
+use crate::msg::{ExecuteMsg, ReceiveMsg};
 
 pub struct House {
   pub address: Addr,
@@ -40,4 +41,66 @@ impl House {
       funds: funds.clone(),
     })
   }
+
+  pub fn build_reserve_msg(
+    &self,
+    amount: Uint128,
+    id: &String,
+  ) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+      contract_addr: self.address.clone().into(),
+      msg: to_binary(&ExecuteMsg::Reserve {
+        amount,
+        id: id.clone(),
+      })?,
+      funds: vec![],
+    })
+  }
+
+  pub fn build_release_msg(
+    &self,
+    id: &String,
+  ) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+      contract_addr: self.address.clone().into(),
+      msg: to_binary(&ExecuteMsg::Release { id: id.clone() })?,
+      funds: vec![],
+    })
+  }
+
+  pub fn build_settle_msg(
+    &self,
+    id: &String,
+    payout: Uint128,
+    recipient: &Addr,
+  ) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+      contract_addr: self.address.clone().into(),
+      msg: to_binary(&ExecuteMsg::Settle {
+        id: id.clone(),
+        payout,
+        recipient: recipient.clone(),
+      })?,
+      funds: vec![],
+    })
+  }
+
+  /// Build a message that sends CW20 tokens to the house as revenue via its
+  /// Receive hook, which doesn't require an allowance.
+  pub fn build_receive_payment_cw20_msg(
+    &self,
+    cw20_address: &Addr,
+    sender: Option<Addr>,
+    amount: Uint128,
+  ) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+      contract_addr: cw20_address.clone().into(),
+      msg: to_binary(&Cw20ExecuteMsg::Send {
+        contract: self.address.clone().into(),
+        amount,
+        msg: to_binary(&ReceiveMsg::ReceivePayment { sender })?,
+      })?,
+      funds: vec![],
+    })
+  }
 }
@@ -1,11 +1,14 @@
+use crate::error::ContractError;
 use crate::execute;
+use crate::migrations;
 use crate::models::ContractResult;
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::query;
 use crate::state;
+use crate::util::parse_version;
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response};
-use cw2::set_contract_version;
+use cosmwasm_std::{attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response};
+use cw2::{get_contract_version, set_contract_version};
 
 const CONTRACT_NAME: &str = "crates.io:house-staking-contract";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -30,37 +33,136 @@ pub fn execute(
   msg: ExecuteMsg,
 ) -> ContractResult<Response> {
   match msg {
-    ExecuteMsg::Delegate { growth, profit } => execute::delegate(deps, env, info, growth, profit),
-    ExecuteMsg::Withdraw {} => execute::withdraw(deps, env, info),
+    ExecuteMsg::Delegate {
+      growth,
+      profit,
+      mint_shares,
+      beneficiary,
+    } => execute::delegate(deps, env, info, growth, profit, mint_shares, beneficiary),
+    ExecuteMsg::Withdraw { growth, profit } => execute::withdraw(deps, env, info, growth, profit),
     ExecuteMsg::SendProfit {} => execute::send_profit(deps, env, info),
-    ExecuteMsg::SetClient { address } => execute::set_client(deps, env, info, &address),
+    ExecuteMsg::Compound { pool, amount } => execute::compound(deps, env, info, pool, amount),
+    ExecuteMsg::TransferAccount { recipient } => {
+      execute::transfer_account(deps, env, info, &recipient)
+    },
+    ExecuteMsg::Rebalance {
+      growth_to_profit,
+      profit_to_growth,
+    } => execute::rebalance(deps, env, info, growth_to_profit, profit_to_growth),
+    ExecuteMsg::SetClient { address, metadata } => {
+      execute::set_client(deps, env, info, &address, metadata)
+    },
+    ExecuteMsg::UpdateClientMetadata { address, metadata } => {
+      execute::update_client_metadata(deps, env, info, &address, metadata)
+    },
+    ExecuteMsg::SetClientLimits { address, limits } => {
+      execute::set_client_limits(deps, env, info, &address, limits)
+    },
+    ExecuteMsg::SetClientStatus { address, status } => {
+      execute::set_client_status(deps, env, info, &address, status)
+    },
+    ExecuteMsg::RemoveClient { address } => execute::remove_client(deps, env, info, &address),
     ExecuteMsg::ReceivePayment { sender, amount } => {
       execute::receive_payment(deps, env, info, sender, amount)
     },
     ExecuteMsg::SendPayment { recipient, amount } => {
       execute::send_payment(deps, env, info, &recipient, amount)
     },
+    ExecuteMsg::Reserve { amount, id } => execute::reserve(deps, env, info, amount, id),
+    ExecuteMsg::Release { id } => execute::release(deps, env, info, id),
+    ExecuteMsg::Settle {
+      id,
+      payout,
+      recipient,
+    } => execute::settle(deps, env, info, id, payout, &recipient),
+    ExecuteMsg::ClaimUnbonded {} => execute::claim_unbonded(deps, env, info),
+    ExecuteMsg::ClaimFees {} => execute::claim_fees(deps, env, info),
+    ExecuteMsg::SetFeeConfig { fee } => execute::set_fee_config(deps, env, info, fee),
+    ExecuteMsg::SetUnbondingPeriod { seconds } => {
+      execute::set_unbonding_period(deps, env, info, seconds)
+    },
+    ExecuteMsg::UpdateConfig { config } => execute::update_config(deps, env, info, config),
+    ExecuteMsg::Amortize { limit } => execute::amortize(deps, env, info, limit),
+    ExecuteMsg::SettleAccount { max_snapshots } => {
+      execute::settle_account(deps, env, info, max_snapshots)
+    },
+    ExecuteMsg::CreateShareToken { config } => execute::create_share_token(deps, env, info, config),
+    ExecuteMsg::Pause { operations } => execute::pause(deps, env, info, operations),
+    ExecuteMsg::Unpause { operations } => execute::unpause(deps, env, info, operations),
+    ExecuteMsg::Receive(cw20_msg) => execute::receive(deps, env, info, cw20_msg),
   }
 }
 
 #[entry_point]
 pub fn query(
   deps: Deps,
-  _env: Env,
+  env: Env,
   msg: QueryMsg,
 ) -> ContractResult<Binary> {
   let result = match msg {
-    QueryMsg::Select { fields, wallet } => to_binary(&query::select(deps, fields, wallet)?),
+    QueryMsg::Select { fields, wallet } => to_binary(&query::select(deps, env, fields, wallet)?),
     QueryMsg::Client { address } => to_binary(&query::get_client(deps, address)?),
+    QueryMsg::Config {} => to_binary(&query::get_config(deps)?),
+    QueryMsg::Invariants {
+      start_after,
+      limit,
+      totals,
+    } => to_binary(&query::invariants(deps, env, start_after, limit, totals)?),
+    QueryMsg::Snapshots {
+      start_after,
+      limit,
+      order,
+    } => to_binary(&query::snapshots(deps, start_after, limit, order)?),
+    QueryMsg::Delegators { start_after, limit } => {
+      to_binary(&query::delegators(deps, env, start_after, limit)?)
+    },
+    QueryMsg::Delegations {
+      wallet,
+      pool,
+      start_after,
+      limit,
+    } => to_binary(&query::delegations(deps, wallet, pool, start_after, limit)?),
+    QueryMsg::Clients { start_after, limit } => {
+      to_binary(&query::clients(deps, start_after, limit)?)
+    },
   }?;
   Ok(result)
 }
 
+#[entry_point]
+pub fn reply(
+  deps: DepsMut,
+  env: Env,
+  reply: Reply,
+) -> ContractResult<Response> {
+  match reply.id {
+    execute::SHARE_TOKEN_REPLY_ID => execute::share_token_created(deps, env, reply),
+    _ => Err(ContractError::NotFound {}),
+  }
+}
+
 #[entry_point]
 pub fn migrate(
-  _deps: DepsMut,
+  mut deps: DepsMut,
   _env: Env,
-  _msg: MigrateMsg,
+  msg: MigrateMsg,
 ) -> ContractResult<Response> {
-  Ok(Response::default())
+  let stored = get_contract_version(deps.storage)?;
+
+  if stored.contract != CONTRACT_NAME {
+    return Err(ContractError::InvalidContractName {});
+  }
+  if parse_version(&stored.version)? > parse_version(CONTRACT_VERSION)? {
+    return Err(ContractError::CannotMigrateToOlderVersion {});
+  }
+
+  migrations::migrate(deps.branch(), &stored.version, &msg)?;
+
+  set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "migrate"),
+    attr("from_version", stored.version),
+    attr("to_version", CONTRACT_VERSION),
+  ]))
 }
@@ -29,4 +29,77 @@ pub enum ContractError {
 
   #[error("InsufficientAllowance")]
   InsufficientAllowance {},
+
+  #[error("Paused")]
+  Paused {},
+
+  #[error("InvalidVersion")]
+  InvalidVersion {},
+
+  #[error("InvalidContractName")]
+  InvalidContractName {},
+
+  #[error("CannotMigrateToOlderVersion")]
+  CannotMigrateToOlderVersion {},
+
+  #[error("InvalidToken")]
+  InvalidToken {},
+
+  #[error("InvalidAmount")]
+  InvalidAmount {},
+
+  #[error("PayoutLimitExceeded")]
+  PayoutLimitExceeded {},
+
+  #[error("WindowPayoutLimitExceeded")]
+  WindowPayoutLimitExceeded {},
+
+  #[error("NetLossLimitExceeded")]
+  NetLossLimitExceeded {},
+
+  #[error("InvalidFee")]
+  InvalidFee {},
+
+  #[error("ReservationExists")]
+  ReservationExists {},
+
+  #[error("PayoutExceedsReservation")]
+  PayoutExceedsReservation {},
+
+  #[error("ShareTokenNotFound")]
+  ShareTokenNotFound {},
+
+  #[error("ShareTokenExists")]
+  ShareTokenExists {},
+
+  #[error("DelegationBelowMinimum")]
+  DelegationBelowMinimum {},
+
+  #[error("LiquidityCapExceeded")]
+  LiquidityCapExceeded {},
+
+  #[error("AccountDelegationCapExceeded")]
+  AccountDelegationCapExceeded {},
+
+  #[error("InvalidConfig")]
+  InvalidConfig {},
+
+  #[error("ClientSuspended")]
+  ClientSuspended {},
+
+  #[error("InvalidClientMetadata")]
+  InvalidClientMetadata {},
+
+  #[error("Overflow: {name}")]
+  Overflow { name: String },
+
+  #[error("Underflow: {name} is {actual}, expected at least {expected}")]
+  Underflow {
+    name: String,
+    expected: String,
+    actual: String,
+  },
+
+  #[error("DivisionByZero: {name}")]
+  DivisionByZero { name: String },
 }
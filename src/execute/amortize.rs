@@ -0,0 +1,32 @@
+use crate::{
+  models::{ContractResult, Operation},
+  state::{amortize_n, ensure_not_paused, CONFIG, MEMOIZATION_QUEUE_LEN},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Memoize the claims of accounts at the front of the memoization queue.
+/// Anyone can call this to keep amortization moving between other operations.
+pub fn amortize(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  limit: Option<u32>,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Amortize)?;
+
+  let limit = match limit {
+    Some(limit) => limit,
+    None => CONFIG.load(deps.storage)?.amortization_batch_size,
+  };
+
+  let n_amortized = amortize_n(deps.storage, limit)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "amortize"),
+    attr("n_amortized", n_amortized.to_string()),
+    attr(
+      "queue_len",
+      MEMOIZATION_QUEUE_LEN.load(deps.storage)?.to_string(),
+    ),
+  ]))
+}
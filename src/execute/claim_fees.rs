@@ -0,0 +1,44 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation},
+  state::{ensure_not_paused, is_allowed, FEE_CONFIG, NET_FEES, TOKEN},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+use cw_lib::utils::funds::build_send_submsg;
+
+pub fn claim_fees(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "claim_fees")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  ensure_not_paused(deps.storage, Operation::ClaimFees)?;
+
+  let recipient = FEE_CONFIG
+    .load(deps.storage)?
+    .recipient
+    .unwrap_or(info.sender.clone());
+
+  let amount = NET_FEES.load(deps.storage)?;
+
+  NET_FEES.save(deps.storage, &Uint128::zero())?;
+
+  let mut resp = Response::new().add_attributes(vec![
+    attr("action", "claim_fees"),
+    attr("amount", amount.to_string()),
+    attr("recipient", recipient.to_string()),
+  ]);
+
+  if !amount.is_zero() {
+    resp = resp.add_submessage(build_send_submsg(
+      &recipient,
+      amount,
+      &TOKEN.load(deps.storage)?,
+    )?);
+  }
+
+  Ok(resp)
+}
@@ -0,0 +1,37 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation, Unbonding},
+  state::{ensure_not_paused, NET_LIQUIDITY, NET_LIQUIDITY_RESERVED, TOKEN},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+use cw_lib::utils::funds::build_send_submsg;
+
+pub fn claim_unbonded(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Withdraw)?;
+
+  let amount = Unbonding::claim_matured(deps.storage, &info.sender, env.block.time)?;
+
+  // liquidity still reserved by clients can't be claimed until it's released
+  if NET_LIQUIDITY.load(deps.storage)? < NET_LIQUIDITY_RESERVED.load(deps.storage)? {
+    return Err(ContractError::InsufficientLiquidity {});
+  }
+
+  let mut resp = Response::new().add_attributes(vec![
+    attr("action", "claim_unbonded"),
+    attr("amount", amount.to_string()),
+  ]);
+
+  if !amount.is_zero() {
+    resp = resp.add_submessage(build_send_submsg(
+      &info.sender,
+      amount,
+      &TOKEN.load(deps.storage)?,
+    )?);
+  }
+
+  Ok(resp)
+}
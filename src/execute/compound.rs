@@ -0,0 +1,38 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, DelegationType, Operation},
+  state::{amortize, ensure_not_paused, DELEGATION_ACCOUNTS},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+
+pub fn compound(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  pool: DelegationType,
+  amount: Option<Uint128>,
+) -> ContractResult<Response> {
+  // compounding both claims profit and delegates it
+  ensure_not_paused(deps.storage, Operation::SendProfit)?;
+  ensure_not_paused(deps.storage, Operation::Delegate)?;
+
+  let mut account = DELEGATION_ACCOUNTS
+    .may_load(deps.storage, info.sender.clone())?
+    .ok_or(ContractError::NotFound {})?;
+
+  let amount = account.compound(deps.storage, pool.clone(), amount)?;
+
+  amortize(deps.storage)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "compound"),
+    attr(
+      "pool",
+      match pool {
+        DelegationType::Growth => "growth",
+        DelegationType::Profit => "profit",
+      },
+    ),
+    attr("amount", amount.to_string()),
+  ]))
+}
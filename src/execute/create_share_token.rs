@@ -0,0 +1,89 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, ShareTokenConfig},
+  state::{is_allowed, SHARE_TOKEN},
+  util::validate_addr,
+};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+  attr, to_binary, Addr, DepsMut, Env, MessageInfo, Reply, Response, StdError, SubMsg, WasmMsg,
+};
+use cw20::{Cw20Coin, MinterResponse};
+use cw_utils::parse_reply_instantiate_data;
+
+pub const SHARE_TOKEN_REPLY_ID: u64 = 1;
+
+/// Instantiate message accepted by cw20-base compatible contracts
+#[cw_serde]
+struct Cw20InstantiateMsg {
+  pub name: String,
+  pub symbol: String,
+  pub decimals: u8,
+  pub initial_balances: Vec<Cw20Coin>,
+  pub mint: Option<MinterResponse>,
+}
+
+/// Instantiate the CW20 share token minted for delegations to the house's
+/// growth pool vault. The house is its only minter.
+pub fn create_share_token(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  config: ShareTokenConfig,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "create_share_token")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  if SHARE_TOKEN.may_load(deps.storage)?.is_some() {
+    return Err(ContractError::ShareTokenExists {});
+  }
+
+  let house_address = env.contract.address.to_string();
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "create_share_token"),
+        attr("code_id", config.code_id.to_string()),
+      ])
+      .add_submessage(SubMsg::reply_on_success(
+        WasmMsg::Instantiate {
+          admin: Some(house_address.clone()),
+          code_id: config.code_id,
+          label: format!("{} house shares", config.symbol),
+          funds: vec![],
+          msg: to_binary(&Cw20InstantiateMsg {
+            name: config.name,
+            symbol: config.symbol,
+            decimals: config.decimals,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+              minter: house_address,
+              cap: None,
+            }),
+          })?,
+        },
+        SHARE_TOKEN_REPLY_ID,
+      )),
+  )
+}
+
+/// Save the address of the newly instantiated share token.
+pub fn share_token_created(
+  deps: DepsMut,
+  _env: Env,
+  reply: Reply,
+) -> ContractResult<Response> {
+  let resp = parse_reply_instantiate_data(reply)
+    .map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
+
+  let share_token = validate_addr(deps.api, &Addr::unchecked(resp.contract_address))?;
+
+  SHARE_TOKEN.save(deps.storage, &share_token)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "share_token_created"),
+    attr("share_token", share_token.to_string()),
+  ]))
+}
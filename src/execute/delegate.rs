@@ -1,9 +1,10 @@
 use crate::{
-  models::{ContractResult, DelegationAccount, DelegationType},
+  models::{ContractResult, DelegationAccount, DelegationType, Operation},
   state::{
-    amortize, DELEGATION_ACCOUNTS, DELEGATION_ACCOUNTS_LEN, MEMOIZATION_QUEUE, NET_LIQUIDITY, TOKEN,
+    amortize, enqueue_memoization, ensure_delegation_allowed, ensure_not_paused,
+    DELEGATION_ACCOUNTS, DELEGATION_ACCOUNTS_LEN, NET_LIQUIDITY, TOKEN,
   },
-  util::increment,
+  util::{add, increment, validate_addr},
 };
 use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, Storage, Timestamp, Uint128};
 use cw_lib::{
@@ -11,42 +12,130 @@ use cw_lib::{
   utils::funds::{build_cw20_transfer_from_submsg, has_funds},
 };
 
+use super::shares::process_mint_shares;
+
 pub fn delegate(
   deps: DepsMut,
   env: Env,
   info: MessageInfo,
   growth_delegation: Uint128,
   profit_delegation: Uint128,
+  mint_shares: Option<bool>,
+  beneficiary: Option<Addr>,
 ) -> ContractResult<Response> {
-  let mut resp = Response::new().add_attributes(vec![attr("action", "stake")]);
-  let total_delegation = growth_delegation + profit_delegation;
+  let total_delegation = add(growth_delegation, profit_delegation, "delegation")?;
 
   if total_delegation.is_zero() {
     return Err(crate::error::ContractError::InsufficientDelegation {});
   }
 
   // check payment amounts and add any necessary submsgs to response:
-  match TOKEN.load(deps.storage)? {
+  let submsg = match TOKEN.load(deps.storage)? {
     Token::Native { denom } => {
       if !has_funds(&info.funds, total_delegation, &denom) {
         return Err(crate::error::ContractError::InsufficientFunds {});
       }
+      None
     },
     Token::Cw20 {
       address: cw20_address,
-    } => {
-      resp = resp.add_submessage(build_cw20_transfer_from_submsg(
-        &info.sender,
-        &env.contract.address,
-        &cw20_address,
-        total_delegation,
-      )?)
+    } => Some(build_cw20_transfer_from_submsg(
+      &info.sender,
+      &env.contract.address,
+      &cw20_address,
+      total_delegation,
+    )?),
+  };
+
+  let resp = process_delegate_for(
+    deps,
+    &env,
+    &info.sender,
+    beneficiary,
+    growth_delegation,
+    profit_delegation,
+    mint_shares,
+  )?;
+
+  Ok(match submsg {
+    Some(submsg) => resp.add_submessage(submsg),
+    None => resp,
+  })
+}
+
+/// Credit the beneficiary, defaulting to the delegator who provided the funds,
+/// with a delegation or with share tokens.
+pub fn process_delegate_for(
+  deps: DepsMut,
+  env: &Env,
+  delegator: &Addr,
+  beneficiary: Option<Addr>,
+  growth_delegation: Uint128,
+  profit_delegation: Uint128,
+  mint_shares: Option<bool>,
+) -> ContractResult<Response> {
+  let beneficiary = match beneficiary {
+    Some(beneficiary) => validate_addr(deps.api, &beneficiary)?,
+    None => delegator.clone(),
+  };
+
+  // the house's own account backs its share token and can't be credited
+  if beneficiary == env.contract.address {
+    return Err(crate::error::ContractError::InvalidAddress {});
+  }
+
+  let resp = if mint_shares.unwrap_or(false) {
+    // shares only represent a claim on the growth pool
+    if !profit_delegation.is_zero() {
+      return Err(crate::error::ContractError::InvalidAmount {});
+    }
+    process_mint_shares(deps, env, &beneficiary, growth_delegation)?
+  } else {
+    process_delegate(
+      deps,
+      env,
+      &beneficiary,
+      growth_delegation,
+      profit_delegation,
+    )?
+  };
+
+  Ok(resp.add_attributes(vec![
+    attr("delegator", delegator.to_string()),
+    attr("beneficiary", beneficiary.to_string()),
+  ]))
+}
+
+/// Credit the delegator with the given growth and profit delegation amounts.
+/// Funds must already be accounted for by the caller.
+pub fn process_delegate(
+  deps: DepsMut,
+  env: &Env,
+  delegator: &Addr,
+  growth_delegation: Uint128,
+  profit_delegation: Uint128,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Delegate)?;
+
+  let total_delegation = add(growth_delegation, profit_delegation, "delegation")?;
+
+  if total_delegation.is_zero() {
+    return Err(crate::error::ContractError::InsufficientDelegation {});
+  }
+
+  let account_delegation = match DELEGATION_ACCOUNTS.may_load(deps.storage, delegator.clone())? {
+    Some(account) => {
+      let (x_deleg_growth, x_deleg_profit) = account.get_delegation_amounts(deps.storage)?;
+      add(x_deleg_growth, x_deleg_profit, "account.delegation")?
     },
+    None => Uint128::zero(),
   };
 
-  let account = get_or_create_account(deps.storage, &info.sender, env.block.time)?;
+  ensure_delegation_allowed(deps.storage, total_delegation, Some(account_delegation))?;
+
+  let mut account = get_or_create_account(deps.storage, delegator, env.block.time)?;
 
-  // create separate Delegation records for both delegation amounts
+  // add both delegation amounts to the account
   if !growth_delegation.is_zero() {
     account.delegate(deps.storage, DelegationType::Growth, growth_delegation)?;
   }
@@ -59,10 +148,10 @@ pub fn delegate(
 
   amortize(deps.storage)?;
 
-  Ok(resp)
+  Ok(Response::new().add_attributes(vec![attr("action", "stake")]))
 }
 
-fn get_or_create_account(
+pub fn get_or_create_account(
   storage: &mut dyn Storage,
   owner: &Addr,
   created_at: Timestamp,
@@ -87,7 +176,7 @@ fn get_or_create_account(
 
     // add the new account to the back of the memoization queue for use
     // by amortization.
-    MEMOIZATION_QUEUE.push_back(storage, owner)?;
+    enqueue_memoization(storage, owner)?;
   }
 
   Ok(account)
@@ -1,13 +1,56 @@
+mod amortize;
+mod claim_fees;
+mod claim_unbonded;
+mod compound;
+mod create_share_token;
 mod delegate;
+mod pause;
+mod rebalance;
+mod receive;
 mod receive_payment;
+mod release;
+mod remove_client;
+mod reserve;
 mod send_payment;
 mod send_profit;
 mod set_client;
+mod set_client_limits;
+mod set_client_status;
+mod set_fee_config;
+mod set_unbonding_period;
+mod settle;
+mod settle_account;
+mod shares;
+mod transfer_account;
+mod unpause;
+mod update_client_metadata;
+mod update_config;
 mod withdraw;
 
+pub use amortize::amortize;
+pub use claim_fees::claim_fees;
+pub use claim_unbonded::claim_unbonded;
+pub use compound::compound;
+pub use create_share_token::{create_share_token, share_token_created, SHARE_TOKEN_REPLY_ID};
 pub use delegate::delegate;
+pub use pause::pause;
+pub use rebalance::rebalance;
+pub use receive::receive;
 pub use receive_payment::receive_payment;
+pub use release::release;
+pub use remove_client::remove_client;
+pub use reserve::reserve;
 pub use send_payment::send_payment;
 pub use send_profit::send_profit;
 pub use set_client::set_client;
+pub use set_client_limits::set_client_limits;
+pub use set_client_status::set_client_status;
+pub use set_fee_config::set_fee_config;
+pub use set_unbonding_period::set_unbonding_period;
+pub use settle::settle;
+pub use settle_account::settle_account;
+pub use transfer_account::transfer_account;
+pub use unpause::unpause;
+pub use update_client_metadata::update_client_metadata;
+pub use update_config::update_config;
 pub use withdraw::withdraw;
@@ -0,0 +1,28 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation},
+  state::{is_allowed, PAUSED_OPERATIONS},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+pub fn pause(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  operations: Vec<Operation>,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "pause")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  PAUSED_OPERATIONS.update(deps.storage, |mut paused| -> ContractResult<_> {
+    for operation in operations.iter() {
+      if !paused.contains(operation) {
+        paused.push(operation.clone());
+      }
+    }
+    Ok(paused)
+  })?;
+
+  Ok(Response::new().add_attributes(vec![attr("action", "pause")]))
+}
@@ -0,0 +1,33 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation},
+  state::{amortize, ensure_not_paused, DELEGATION_ACCOUNTS},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+
+pub fn rebalance(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  growth_to_profit: Option<Uint128>,
+  profit_to_growth: Option<Uint128>,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Delegate)?;
+
+  let growth_to_profit = growth_to_profit.unwrap_or_default();
+  let profit_to_growth = profit_to_growth.unwrap_or_default();
+
+  let mut account = DELEGATION_ACCOUNTS
+    .may_load(deps.storage, info.sender.clone())?
+    .ok_or(ContractError::NotFound {})?;
+
+  account.rebalance(deps.storage, growth_to_profit, profit_to_growth)?;
+
+  amortize(deps.storage)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "rebalance"),
+    attr("growth_to_profit", growth_to_profit.to_string()),
+    attr("profit_to_growth", profit_to_growth.to_string()),
+  ]))
+}
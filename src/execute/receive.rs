@@ -0,0 +1,81 @@
+use crate::{
+  error::ContractError,
+  models::ContractResult,
+  msg::ReceiveMsg,
+  state::{SHARE_TOKEN, TOKEN},
+  util::{add, validate_addr},
+};
+use cosmwasm_std::{attr, from_binary, Addr, DepsMut, Env, MessageInfo, Response};
+use cw20::Cw20ReceiveMsg;
+use cw_lib::models::Token;
+
+use super::{
+  delegate::process_delegate_for, receive_payment::process_receive_payment,
+  shares::process_redeem_shares,
+};
+
+/// CW20 Receive hook. The tokens have already been transferred to the house by
+/// the time this runs, so no allowance is needed.
+pub fn receive(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20_msg: Cw20ReceiveMsg,
+) -> ContractResult<Response> {
+  let cw20_sender = validate_addr(deps.api, &Addr::unchecked(cw20_msg.sender))?;
+  let amount = cw20_msg.amount;
+  let msg = from_binary::<ReceiveMsg>(&cw20_msg.msg)?;
+
+  // share tokens sent back to the house are redeemed
+  if let Some(share_token) = SHARE_TOKEN.may_load(deps.storage)? {
+    if info.sender == share_token {
+      return match msg {
+        ReceiveMsg::Redeem {} => process_redeem_shares(deps, &env, &cw20_sender, amount),
+        _ => Err(ContractError::InvalidToken {}),
+      };
+    }
+  }
+
+  // otherwise, only accept tokens from the house's own CW20 contract
+  match TOKEN.load(deps.storage)? {
+    Token::Cw20 { address } => {
+      if info.sender != address {
+        return Err(ContractError::InvalidToken {});
+      }
+    },
+    Token::Native { .. } => return Err(ContractError::InvalidToken {}),
+  }
+
+  match msg {
+    ReceiveMsg::Delegate {
+      growth,
+      profit,
+      mint_shares,
+      beneficiary,
+    } => {
+      if add(growth, profit, "delegation")? != amount {
+        return Err(ContractError::InvalidAmount {});
+      }
+      process_delegate_for(
+        deps,
+        &env,
+        &cw20_sender,
+        beneficiary,
+        growth,
+        profit,
+        mint_shares,
+      )
+    },
+    ReceiveMsg::ReceivePayment { sender } => {
+      // the client contract is the one sending the tokens; the optional
+      // sender is whoever originally paid the client.
+      let sender = sender.unwrap_or(cw20_sender.clone());
+      validate_addr(deps.api, &sender)?;
+      Ok(
+        process_receive_payment(deps, &cw20_sender, amount)?
+          .add_attributes(vec![attr("sender", sender.to_string())]),
+      )
+    },
+    ReceiveMsg::Redeem {} => Err(ContractError::InvalidToken {}),
+  }
+}
@@ -1,11 +1,11 @@
 use crate::{
   error::ContractError,
-  models::{ContractResult, Snapshot},
+  models::{ContractResult, Operation, PoolIndices},
   state::{
-    amortize, CLIENT_ACCOUNTS, NET_GROWTH_DELEGATION, NET_LIQUIDITY, NET_PROFIT,
-    NET_PROFIT_DELEGATION, TOKEN,
+    amortize, ensure_not_paused, CLIENT_ACCOUNTS, FEE_CONFIG, LIFETIME_FEES, NET_FEES,
+    NET_GROWTH_DELEGATION, NET_LIQUIDITY, NET_PROFIT, NET_PROFIT_DELEGATION, TOKEN,
   },
-  util::increment,
+  util::{add, increment, mul_ratio, sub},
 };
 use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, Uint128};
 use cw_lib::{
@@ -21,10 +21,51 @@ pub fn receive_payment(
   amount: Uint128,
 ) -> ContractResult<Response> {
   let sender = sender.unwrap_or(info.sender.clone());
-  let mut resp = Response::new().add_attributes(vec![attr("action", "receive_payment")]);
 
   deps.api.addr_validate(sender.as_str())?;
 
+  if amount.is_zero() {
+    return Ok(Response::new().add_attributes(vec![attr("action", "receive_payment")]));
+  }
+
+  // verify funding and add any necessary transfer submsg to response
+  let submsg = match TOKEN.load(deps.storage)? {
+    Token::Native { denom } => {
+      if !has_funds(&info.funds, amount, &denom) {
+        return Err(crate::error::ContractError::InsufficientFunds {});
+      }
+      None
+    },
+    Token::Cw20 {
+      address: cw20_address,
+    } => Some(build_cw20_transfer_from_submsg(
+      &sender,
+      &env.contract.address,
+      &cw20_address,
+      amount,
+    )?),
+  };
+
+  let resp = process_receive_payment(deps, &info.sender, amount)?;
+
+  Ok(match submsg {
+    Some(submsg) => resp.add_submessage(submsg),
+    None => resp,
+  })
+}
+
+/// Distribute revenue received from the given client between the house's
+/// liquidity and profit pools. Funds must already be accounted for by the
+/// caller.
+pub fn process_receive_payment(
+  deps: DepsMut,
+  client: &Addr,
+  amount: Uint128,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::ReceivePayment)?;
+
+  let mut resp = Response::new().add_attributes(vec![attr("action", "receive_payment")]);
+
   if amount.is_zero() {
     return Ok(resp);
   }
@@ -32,10 +73,11 @@ pub fn receive_payment(
   // tally client total historical payment amount received
   CLIENT_ACCOUNTS.update(
     deps.storage,
-    info.sender.clone(),
+    client.clone(),
     |maybe_client| -> ContractResult<_> {
       if let Some(mut client) = maybe_client {
-        client.amount_received += amount;
+        client.ensure_active()?;
+        client.amount_received = add(client.amount_received, amount, "client.amount_received")?;
         Ok(client)
       } else {
         Err(ContractError::NotAuthorized {})
@@ -43,32 +85,33 @@ pub fn receive_payment(
     },
   )?;
 
-  // verify funding and add any necessary transfer submsg to response
-  match TOKEN.load(deps.storage)? {
-    Token::Native { denom } => {
-      if !has_funds(&info.funds, amount, &denom) {
-        return Err(crate::error::ContractError::InsufficientFunds {});
-      }
-    },
-    Token::Cw20 {
-      address: cw20_address,
-    } => {
-      resp = resp.add_submessage(build_cw20_transfer_from_submsg(
-        &sender,
-        &env.contract.address,
-        &cw20_address,
-        amount,
-      )?)
-    },
-  };
+  // take the house's fee off the top before splitting the rest between the
+  // liquidity and profit pools
+  let fee = FEE_CONFIG.load(deps.storage)?.get_fee(amount);
+  if !fee.is_zero() {
+    increment(deps.storage, &NET_FEES, fee)?;
+    increment(deps.storage, &LIFETIME_FEES, fee)?;
+    resp = resp.add_attribute("fee", fee.to_string());
+  }
+
+  let amount = sub(amount, fee, "payment")?;
 
   let net_growth_delegation = NET_GROWTH_DELEGATION.load(deps.storage)?;
   let net_profit_delegation = NET_PROFIT_DELEGATION.load(deps.storage)?;
-  let net_delegation = net_growth_delegation + net_profit_delegation;
+  let net_delegation = add(
+    net_growth_delegation,
+    net_profit_delegation,
+    "net_delegation",
+  )?;
 
   // increase NET_LIQUIDITY
   let liquidity_delta = if !net_delegation.is_zero() {
-    amount.multiply_ratio(net_growth_delegation, net_delegation)
+    mul_ratio(
+      amount,
+      net_growth_delegation,
+      net_delegation,
+      "net_delegation",
+    )?
   } else {
     amount
   };
@@ -77,13 +120,22 @@ pub fn receive_payment(
   }
 
   // increase NET_PROFIT
-  let profit_delta = amount.multiply_ratio(net_profit_delegation, net_delegation);
+  let profit_delta = if !net_delegation.is_zero() {
+    mul_ratio(
+      amount,
+      net_profit_delegation,
+      net_delegation,
+      "net_delegation",
+    )?
+  } else {
+    Uint128::zero()
+  };
   if !profit_delta.is_zero() {
     increment(deps.storage, &NET_PROFIT, profit_delta)?;
   }
 
-  // create a new delegation snapshot
-  Snapshot::upsert(deps.storage, amount, Uint128::zero())?;
+  // credit the revenue to delegators in both pools
+  PoolIndices::accrue_revenue(deps.storage, liquidity_delta, profit_delta)?;
 
   amortize(deps.storage)?;
 
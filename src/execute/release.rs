@@ -0,0 +1,19 @@
+use crate::models::{ContractResult, Reservation};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Release a reservation without paying anything out. This only frees up
+/// liquidity, so it isn't subject to pausing send_payment.
+pub fn release(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  id: String,
+) -> ContractResult<Response> {
+  let reservation = Reservation::release(deps.storage, &info.sender, &id)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "release"),
+    attr("id", id),
+    attr("amount", reservation.amount.to_string()),
+  ]))
+}
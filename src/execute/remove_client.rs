@@ -0,0 +1,44 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Reservation},
+  state::{is_allowed, CLIENT_ACCOUNTS, CLIENT_ACCOUNTS_LEN, RESERVATIONS},
+  util::{decrement, validate_addr},
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Order, Response};
+
+/// Remove a client, releasing any liquidity it still has reserved. To revoke
+/// a client while keeping its payment totals, suspend it instead.
+pub fn remove_client(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  address: &Addr,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "remove_client")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  validate_addr(deps.api, address)?;
+
+  if !CLIENT_ACCOUNTS.has(deps.storage, address.clone()) {
+    return Err(ContractError::NotFound {});
+  }
+
+  let reservation_ids = RESERVATIONS
+    .prefix(address.clone())
+    .keys(deps.storage, None, None, Order::Ascending)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  for id in reservation_ids.iter() {
+    Reservation::release(deps.storage, address, id)?;
+  }
+
+  CLIENT_ACCOUNTS.remove(deps.storage, address.clone());
+  decrement(deps.storage, &CLIENT_ACCOUNTS_LEN, 1)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "remove_client"),
+    attr("client_address", address.to_string()),
+    attr("n_reservations_released", reservation_ids.len().to_string()),
+  ]))
+}
@@ -0,0 +1,33 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation, Reservation},
+  state::{ensure_not_paused, CLIENT_ACCOUNTS},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+
+pub fn reserve(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  amount: Uint128,
+  id: String,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::SendPayment)?;
+
+  CLIENT_ACCOUNTS
+    .may_load(deps.storage, info.sender.clone())?
+    .ok_or(ContractError::NotAuthorized {})?
+    .ensure_active()?;
+
+  if amount.is_zero() {
+    return Err(ContractError::InvalidAmount {});
+  }
+
+  Reservation::create(deps.storage, &info.sender, &id, amount, env.block.time)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "reserve"),
+    attr("id", id),
+    attr("amount", amount.to_string()),
+  ]))
+}
@@ -1,26 +1,42 @@
 use crate::{
   error::ContractError,
-  models::{ContractResult, Snapshot},
-  state::{amortize, CLIENT_ACCOUNTS, NET_LIQUIDITY, TOKEN},
-  util::{decrement, validate_addr},
+  models::{ContractResult, Operation, PoolIndices, UnbondingPool},
+  state::{amortize, ensure_not_paused, get_free_liquidity, CLIENT_ACCOUNTS, NET_LIQUIDITY, TOKEN},
+  util::{decrement, sub, validate_addr},
 };
 use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, Uint128};
 use cw_lib::utils::funds::build_send_submsg;
 
 pub fn send_payment(
   deps: DepsMut,
-  _env: Env,
+  env: Env,
   info: MessageInfo,
   recipient: &Addr,
   amount: Uint128,
 ) -> ContractResult<Response> {
+  Ok(
+    process_send_payment(deps, &env, &info.sender, recipient, amount)?.add_attributes(vec![
+      attr("action", "send_payment"),
+      attr("amount", amount.to_string()),
+      attr("recipient", recipient.to_string()),
+    ]),
+  )
+}
+
+/// Pay the recipient out of the house's free liquidity on behalf of the given
+/// client, returning a Response with the necessary transfer submsg.
+pub fn process_send_payment(
+  deps: DepsMut,
+  env: &Env,
+  client: &Addr,
+  recipient: &Addr,
+  amount: Uint128,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::SendPayment)?;
+
   validate_addr(deps.api, recipient)?;
 
-  let resp = Response::new().add_attributes(vec![
-    attr("action", "send_payment"),
-    attr("amount", amount.to_string()),
-    attr("recipient", recipient.to_string()),
-  ]);
+  let resp = Response::new();
 
   if amount.is_zero() {
     return Ok(resp);
@@ -29,11 +45,12 @@ pub fn send_payment(
   // update client data if exists or error:
   CLIENT_ACCOUNTS.update(
     deps.storage,
-    info.sender.clone(),
+    client.clone(),
     |maybe_client| -> ContractResult<_> {
       if let Some(mut client) = maybe_client {
+        client.ensure_active()?;
         // tally client total historical payment amount sent
-        client.amount_spent += amount;
+        client.spend(env.block.time, amount)?;
         Ok(client)
       } else {
         Err(ContractError::NotAuthorized {})
@@ -41,8 +58,16 @@ pub fn send_payment(
     },
   )?;
 
-  // create a new delegation snapshot
-  Snapshot::upsert(deps.storage, Uint128::zero(), amount)?;
+  // liquidity reserved by other clients or held for unbondings can't be spent
+  if amount > get_free_liquidity(deps.storage)? {
+    return Err(ContractError::InsufficientLiquidity {});
+  }
+
+  // pending unbondings absorb their share of the loss
+  let unbonding_loss = UnbondingPool::absorb_loss(deps.storage, amount)?;
+
+  // the growth pool absorbs the rest of the loss
+  PoolIndices::accrue_loss(deps.storage, sub(amount, unbonding_loss, "payment")?)?;
 
   // remove payment amount from contract-level liquidity amount
   decrement(deps.storage, &NET_LIQUIDITY, amount)?;
@@ -1,6 +1,7 @@
 use crate::{
-  models::{ContractResult, DelegationAccount},
-  state::{DELEGATION_ACCOUNTS, NET_PROFIT, TOKEN},
+  models::{ContractResult, DelegationAccount, Operation},
+  state::{ensure_not_paused, DELEGATION_ACCOUNTS, NET_PROFIT, TOKEN},
+  util::add,
 };
 use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
 use cw_lib::utils::funds::build_send_submsg;
@@ -10,6 +11,8 @@ pub fn send_profit(
   _env: Env,
   info: MessageInfo,
 ) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::SendProfit)?;
+
   let mut profit =
     if let Some(mut account) = DELEGATION_ACCOUNTS.may_load(deps.storage, info.sender.clone())? {
       account.send_profit(deps.storage)?
@@ -19,7 +22,7 @@ pub fn send_profit(
 
   if DelegationAccount::get_count(deps.storage)? == 1 {
     NET_PROFIT.update(deps.storage, |dust| -> ContractResult<_> {
-      profit += dust;
+      profit = add(profit, dust, "profit")?;
       Ok(Uint128::zero())
     })?;
   }
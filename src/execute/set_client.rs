@@ -1,15 +1,16 @@
 use crate::{
-  models::{ClientAccount, ContractResult},
-  state::{is_allowed, CLIENT_ACCOUNTS, CLIENT_ACCOUNTS_LEN},
+  models::{ClientAccount, ClientMetadata, ContractResult},
+  state::{is_allowed, validate_client_metadata, CLIENT_ACCOUNTS, CLIENT_ACCOUNTS_LEN},
   util::{increment, validate_addr},
 };
-use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
 
 pub fn set_client(
   deps: DepsMut,
   env: Env,
   info: MessageInfo,
   address: &Addr,
+  metadata: Option<ClientMetadata>,
 ) -> ContractResult<Response> {
   if !is_allowed(&deps.as_ref(), &info.sender, "set_client")? {
     return Err(crate::error::ContractError::NotAuthorized {});
@@ -17,24 +18,27 @@ pub fn set_client(
 
   validate_addr(deps.api, address)?;
 
+  if let Some(metadata) = &metadata {
+    validate_client_metadata(metadata)?;
+  }
+
   let mut is_new_account = false;
 
-  // upsert a client account
+  // upsert a client account, replacing its metadata if any is given
   CLIENT_ACCOUNTS.update(
     deps.storage,
     address.clone(),
     |maybe_account| -> ContractResult<_> {
-      if let Some(account) = maybe_account {
-        Ok(account)
+      let mut account = if let Some(account) = maybe_account {
+        account
       } else {
         is_new_account = true;
-        Ok(ClientAccount {
-          owner: address.clone(),
-          created_at: env.block.time,
-          amount_received: Uint128::zero(),
-          amount_spent: Uint128::zero(),
-        })
+        ClientAccount::new(address, env.block.time)
+      };
+      if let Some(metadata) = metadata {
+        account.metadata = metadata;
       }
+      Ok(account)
     },
   )?;
 
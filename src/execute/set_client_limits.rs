@@ -0,0 +1,39 @@
+use crate::{
+  error::ContractError,
+  models::{ClientLimits, ContractResult},
+  state::{is_allowed, CLIENT_ACCOUNTS},
+  util::validate_addr,
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+pub fn set_client_limits(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  address: &Addr,
+  limits: Option<ClientLimits>,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "set_client_limits")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  validate_addr(deps.api, address)?;
+
+  CLIENT_ACCOUNTS.update(
+    deps.storage,
+    address.clone(),
+    |maybe_account| -> ContractResult<_> {
+      if let Some(mut account) = maybe_account {
+        account.limits = limits;
+        Ok(account)
+      } else {
+        Err(ContractError::NotFound {})
+      }
+    },
+  )?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_client_limits"),
+    attr("client_address", address.to_string()),
+  ]))
+}
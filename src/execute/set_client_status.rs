@@ -0,0 +1,48 @@
+use crate::{
+  error::ContractError,
+  models::{ClientStatus, ContractResult},
+  state::{is_allowed, CLIENT_ACCOUNTS},
+  util::validate_addr,
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+/// Suspend or re-enable a client. Suspended clients can't send or receive
+/// payments or reserve liquidity, but their account and totals are kept.
+pub fn set_client_status(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  address: &Addr,
+  status: ClientStatus,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "set_client_status")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  validate_addr(deps.api, address)?;
+
+  CLIENT_ACCOUNTS.update(
+    deps.storage,
+    address.clone(),
+    |maybe_account| -> ContractResult<_> {
+      if let Some(mut account) = maybe_account {
+        account.status = status.clone();
+        Ok(account)
+      } else {
+        Err(ContractError::NotFound {})
+      }
+    },
+  )?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_client_status"),
+    attr("client_address", address.to_string()),
+    attr(
+      "status",
+      match status {
+        ClientStatus::Active => "active",
+        ClientStatus::Suspended => "suspended",
+      },
+    ),
+  ]))
+}
@@ -0,0 +1,26 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, FeeConfig},
+  state::{is_allowed, validate_fee_config, FEE_CONFIG},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+pub fn set_fee_config(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  fee: FeeConfig,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "set_fee_config")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  validate_fee_config(deps.api, &fee)?;
+
+  FEE_CONFIG.save(deps.storage, &fee)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_fee_config"),
+    attr("rate", fee.rate.to_string()),
+  ]))
+}
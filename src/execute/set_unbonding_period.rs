@@ -0,0 +1,24 @@
+use crate::{
+  error::ContractError,
+  models::ContractResult,
+  state::{is_allowed, UNBONDING_PERIOD},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+pub fn set_unbonding_period(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  seconds: u64,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "set_unbonding_period")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  UNBONDING_PERIOD.save(deps.storage, &seconds)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_unbonding_period"),
+    attr("seconds", seconds.to_string()),
+  ]))
+}
@@ -0,0 +1,33 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Reservation},
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, Uint128};
+
+use super::send_payment::process_send_payment;
+
+/// Release a reservation and pay out the final amount owed to the recipient
+/// using the liquidity it freed up.
+pub fn settle(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  id: String,
+  payout: Uint128,
+  recipient: &Addr,
+) -> ContractResult<Response> {
+  let reservation = Reservation::release(deps.storage, &info.sender, &id)?;
+
+  if payout > reservation.amount {
+    return Err(ContractError::PayoutExceedsReservation {});
+  }
+
+  Ok(
+    process_send_payment(deps, &env, &info.sender, recipient, payout)?.add_attributes(vec![
+      attr("action", "settle"),
+      attr("id", id),
+      attr("amount", payout.to_string()),
+      attr("recipient", recipient.to_string()),
+    ]),
+  )
+}
@@ -0,0 +1,34 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation},
+  state::{ensure_not_paused, DELEGATION_ACCOUNTS},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Process up to `max_snapshots` of the sender's legacy snapshots into its
+/// memoized claims. An account idle through a long snapshot backlog can clear
+/// it over several transactions this way, leaving withdrawals only the rest.
+pub fn settle_account(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  max_snapshots: u32,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Amortize)?;
+
+  let mut account = DELEGATION_ACCOUNTS
+    .may_load(deps.storage, info.sender.clone())?
+    .ok_or(ContractError::NotFound {})?;
+
+  let (n_snapshots, is_settled) =
+    account.settle_claims_bounded(deps.storage, Some(max_snapshots))?;
+
+  DELEGATION_ACCOUNTS.save(deps.storage, info.sender.clone(), &account)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "settle_account"),
+    attr("owner", info.sender.to_string()),
+    attr("n_snapshots", n_snapshots.to_string()),
+    attr("is_settled", is_settled.to_string()),
+  ]))
+}
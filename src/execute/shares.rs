@@ -0,0 +1,224 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, DelegationAccount, DelegationType, Operation, Unbonding},
+  state::{
+    amortize, ensure_account_delegation_allowed, ensure_delegation_allowed,
+    ensure_liquidity_unlocked, ensure_not_paused, DELEGATION_ACCOUNTS, NET_LIQUIDITY, SHARE_SUPPLY,
+    SHARE_TOKEN, TOKEN, UNBONDING_PERIOD,
+  },
+  util::{add, decrement, increment, mul_ratio},
+};
+use cosmwasm_std::{
+  attr, to_binary, Addr, Deps, DepsMut, Env, Response, Storage, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_lib::utils::funds::build_send_submsg;
+
+// Share tokens are a pro-rata claim on the house's growth pool vault, which
+// is a DelegationAccount owned by the house itself. The vault's growth and
+// loss are restaked before minting or redeeming, so that each share is worth
+// the vault's growth delegation divided by the share supply. This is the
+// vault's value rather than NET_LIQUIDITY, most of which is owed to ordinary
+// delegation accounts that have nothing to do with the shares. The vault isn't
+// counted as a delegation account and never enters the memoization queue;
+// its claims are settled whenever shares are minted or redeemed.
+
+/// Delegate the given amount to the vault's growth pool, minting shares to
+/// the recipient. Funds must already be accounted for by the caller.
+pub fn process_mint_shares(
+  deps: DepsMut,
+  env: &Env,
+  recipient: &Addr,
+  amount: Uint128,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Delegate)?;
+
+  if amount.is_zero() {
+    return Err(ContractError::InsufficientDelegation {});
+  }
+
+  // the vault itself isn't subject to the per-account cap, but the recipient
+  // is, further down
+  ensure_delegation_allowed(deps.storage, amount, None)?;
+
+  let share_token = SHARE_TOKEN
+    .may_load(deps.storage)?
+    .ok_or(ContractError::ShareTokenNotFound {})?;
+
+  let mut vault = get_or_create_vault(deps.storage, env)?;
+  let vault_value = vault.restake_growth(deps.storage)?;
+  let share_supply = SHARE_SUPPLY.load(deps.storage)?;
+
+  // the recipient's cap covers their own account along with the value of
+  // the shares they already hold
+  let held_shares = query_share_balance(deps.as_ref(), &share_token, recipient)?;
+  let account_delegation = add(
+    get_account_delegation(deps.storage, recipient)?,
+    if share_supply.is_zero() {
+      Uint128::zero()
+    } else {
+      mul_ratio(vault_value, held_shares, share_supply, "share_supply")?
+    },
+    "account.delegation",
+  )?;
+  ensure_account_delegation_allowed(deps.storage, amount, account_delegation)?;
+
+  let shares = if share_supply.is_zero() {
+    amount
+  } else if vault_value.is_zero() {
+    // outstanding shares have been wiped out by losses
+    return Err(ContractError::InsufficientLiquidity {});
+  } else {
+    mul_ratio(amount, share_supply, vault_value, "share_supply")?
+  };
+
+  if shares.is_zero() {
+    return Err(ContractError::InvalidAmount {});
+  }
+
+  vault.delegate(deps.storage, DelegationType::Growth, amount)?;
+
+  increment(deps.storage, &NET_LIQUIDITY, amount)?;
+  increment(deps.storage, &SHARE_SUPPLY, shares)?;
+
+  amortize(deps.storage)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "mint_shares"),
+        attr("recipient", recipient.to_string()),
+        attr("amount", amount.to_string()),
+        attr("shares", shares.to_string()),
+      ])
+      .add_message(WasmMsg::Execute {
+        contract_addr: share_token.into(),
+        msg: to_binary(&Cw20ExecuteMsg::Mint {
+          recipient: recipient.to_string(),
+          amount: shares,
+        })?,
+        funds: vec![],
+      }),
+  )
+}
+
+/// Burn shares already sent to the house, withdrawing their value from the
+/// vault's growth pool for the owner. Like a withdrawal, the liquidity starts
+/// unbonding if the house has an unbonding period.
+pub fn process_redeem_shares(
+  deps: DepsMut,
+  env: &Env,
+  owner: &Addr,
+  shares: Uint128,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Withdraw)?;
+
+  let share_token = SHARE_TOKEN
+    .may_load(deps.storage)?
+    .ok_or(ContractError::ShareTokenNotFound {})?;
+
+  let share_supply = SHARE_SUPPLY.load(deps.storage)?;
+
+  if shares.is_zero() || shares > share_supply {
+    return Err(ContractError::InvalidAmount {});
+  }
+
+  let mut vault = DELEGATION_ACCOUNTS
+    .may_load(deps.storage, env.contract.address.clone())?
+    .ok_or(ContractError::NotFound {})?;
+
+  let vault_value = vault.restake_growth(deps.storage)?;
+  let amount = mul_ratio(vault_value, shares, share_supply, "share_supply")?;
+
+  decrement(deps.storage, &SHARE_SUPPLY, shares)?;
+
+  let liquidity = if amount.is_zero() {
+    amount
+  } else {
+    vault.withdraw_partial(deps.storage, amount, Uint128::zero())?
+  };
+
+  let unbonding_period = UNBONDING_PERIOD.load(deps.storage)?;
+  let (amount, unbonding_amount) = if unbonding_period == 0 {
+    ensure_liquidity_unlocked(deps.storage)?;
+    (liquidity, Uint128::zero())
+  } else {
+    if !liquidity.is_zero() {
+      Unbonding::create(
+        deps.storage,
+        owner,
+        liquidity,
+        env.block.time,
+        env.block.time.plus_seconds(unbonding_period),
+      )?;
+    }
+    (Uint128::zero(), liquidity)
+  };
+
+  amortize(deps.storage)?;
+
+  let mut resp = Response::new()
+    .add_attributes(vec![
+      attr("action", "redeem_shares"),
+      attr("owner", owner.to_string()),
+      attr("shares", shares.to_string()),
+      attr("amount", amount.to_string()),
+      attr("unbonding_amount", unbonding_amount.to_string()),
+    ])
+    .add_message(WasmMsg::Execute {
+      contract_addr: share_token.into(),
+      msg: to_binary(&Cw20ExecuteMsg::Burn { amount: shares })?,
+      funds: vec![],
+    });
+
+  if !amount.is_zero() {
+    resp = resp.add_submessage(build_send_submsg(
+      owner,
+      amount,
+      &TOKEN.load(deps.storage)?,
+    )?);
+  }
+
+  Ok(resp)
+}
+
+/// Return the total amount delegated by the owner's DelegationAccount, if any.
+fn get_account_delegation(
+  storage: &dyn Storage,
+  owner: &Addr,
+) -> ContractResult<Uint128> {
+  match DELEGATION_ACCOUNTS.may_load(storage, owner.clone())? {
+    Some(account) => {
+      let (growth, profit) = account.get_delegation_amounts(storage)?;
+      add(growth, profit, "account.delegation")
+    },
+    None => Ok(Uint128::zero()),
+  }
+}
+
+fn query_share_balance(
+  deps: Deps,
+  share_token: &Addr,
+  owner: &Addr,
+) -> ContractResult<Uint128> {
+  let resp: BalanceResponse = deps.querier.query_wasm_smart(
+    share_token,
+    &Cw20QueryMsg::Balance {
+      address: owner.to_string(),
+    },
+  )?;
+  Ok(resp.balance)
+}
+
+/// Load the vault's DelegationAccount, creating it if this is the first time
+/// shares are minted.
+fn get_or_create_vault(
+  storage: &dyn Storage,
+  env: &Env,
+) -> ContractResult<DelegationAccount> {
+  Ok(
+    DELEGATION_ACCOUNTS
+      .may_load(storage, env.contract.address.clone())?
+      .unwrap_or_else(|| DelegationAccount::new(&env.contract.address, env.block.time)),
+  )
+}
@@ -0,0 +1,36 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation},
+  state::{amortize, ensure_not_paused, DELEGATION_ACCOUNTS},
+  util::validate_addr,
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+pub fn transfer_account(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  recipient: &Addr,
+) -> ContractResult<Response> {
+  ensure_not_paused(deps.storage, Operation::Withdraw)?;
+  validate_addr(deps.api, recipient)?;
+
+  // the house's own account backs its share token and can't be merged into
+  if *recipient == info.sender || *recipient == env.contract.address {
+    return Err(ContractError::InvalidAddress {});
+  }
+
+  let mut account = DELEGATION_ACCOUNTS
+    .may_load(deps.storage, info.sender.clone())?
+    .ok_or(ContractError::NotFound {})?;
+
+  account.transfer(deps.storage, recipient)?;
+
+  amortize(deps.storage)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "transfer_account"),
+    attr("owner", info.sender.to_string()),
+    attr("recipient", recipient.to_string()),
+  ]))
+}
@@ -0,0 +1,24 @@
+use crate::{
+  error::ContractError,
+  models::{ContractResult, Operation},
+  state::{is_allowed, PAUSED_OPERATIONS},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+pub fn unpause(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  operations: Vec<Operation>,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "unpause")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  PAUSED_OPERATIONS.update(deps.storage, |mut paused| -> ContractResult<_> {
+    paused.retain(|operation| !operations.contains(operation));
+    Ok(paused)
+  })?;
+
+  Ok(Response::new().add_attributes(vec![attr("action", "unpause")]))
+}
@@ -0,0 +1,41 @@
+use crate::{
+  error::ContractError,
+  models::{ClientMetadata, ContractResult},
+  state::{is_allowed, validate_client_metadata, CLIENT_ACCOUNTS},
+  util::validate_addr,
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+/// Replace an existing client's metadata.
+pub fn update_client_metadata(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  address: &Addr,
+  metadata: ClientMetadata,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "update_client_metadata")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  validate_addr(deps.api, address)?;
+  validate_client_metadata(&metadata)?;
+
+  CLIENT_ACCOUNTS.update(
+    deps.storage,
+    address.clone(),
+    |maybe_account| -> ContractResult<_> {
+      if let Some(mut account) = maybe_account {
+        account.metadata = metadata;
+        Ok(account)
+      } else {
+        Err(ContractError::NotFound {})
+      }
+    },
+  )?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "update_client_metadata"),
+    attr("client_address", address.to_string()),
+  ]))
+}
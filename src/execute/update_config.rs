@@ -0,0 +1,23 @@
+use crate::{
+  error::ContractError,
+  models::{Config, ContractResult},
+  state::{is_allowed, validate_config, CONFIG},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+pub fn update_config(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  config: Config,
+) -> ContractResult<Response> {
+  if !is_allowed(&deps.as_ref(), &info.sender, "update_config")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  validate_config(&config)?;
+
+  CONFIG.save(deps.storage, &config)?;
+
+  Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+}
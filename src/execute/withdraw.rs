@@ -1,53 +1,77 @@
 use crate::{
-  models::{ContractResult, DelegationAccount},
-  state::{DELEGATION_ACCOUNTS, DELEGATION_ACCOUNTS_LEN, NET_LIQUIDITY, NET_PROFIT, TOKEN},
-  util::decrement,
+  error::ContractError,
+  models::{ContractResult, DelegationAccount, Operation, Unbonding},
+  state::{
+    dequeue_memoization, ensure_liquidity_unlocked, ensure_not_paused, get_free_liquidity,
+    DELEGATION_ACCOUNTS, DELEGATION_ACCOUNTS_LEN, NET_GROWTH_DELEGATION, NET_LIQUIDITY, NET_PROFIT,
+    TOKEN, UNBONDING_PERIOD,
+  },
+  util::{add, decrement},
 };
-use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
 use cw_lib::utils::funds::build_send_submsg;
 
 pub fn withdraw(
   deps: DepsMut,
-  _env: Env,
+  env: Env,
   info: MessageInfo,
+  growth: Option<Uint128>,
+  profit: Option<Uint128>,
 ) -> ContractResult<Response> {
-  // total number oxisting delegation acounts:
-  let n_accounts = DelegationAccount::get_count(deps.storage)?;
-
-  // process the DelegationAccount's outstanding delegation, claiming whatever liquidity
-  // and profit is owed.
-  let amount =
-    if let Some(account) = DELEGATION_ACCOUNTS.may_load(deps.storage, info.sender.clone())? {
-      let mut amount = account.withdraw(deps.storage)?;
-
-      // adjust contract-level profit and liquidity accumulators:
-      if n_accounts == 1 {
-        NET_PROFIT.update(deps.storage, |dust| -> ContractResult<_> {
-          amount += dust;
-          Ok(Uint128::zero())
-        })?;
-
-        NET_LIQUIDITY.update(deps.storage, |dust| -> ContractResult<_> {
-          amount += dust;
-          Ok(Uint128::zero())
-        })?;
-      }
+  ensure_not_paused(deps.storage, Operation::Withdraw)?;
 
-      // remove the account
-      DELEGATION_ACCOUNTS.remove(deps.storage, info.sender.clone());
+  let (liquidity, profit) =
+    if let Some(mut account) = DELEGATION_ACCOUNTS.may_load(deps.storage, info.sender.clone())? {
+      if growth.is_none() && profit.is_none() {
+        withdraw_all(deps.storage, &mut account)?
+      } else {
+        let growth = growth.unwrap_or_default();
+        let profit = profit.unwrap_or_default();
+        let (x_deleg_growth, x_deleg_profit) = account.get_delegation_amounts(deps.storage)?;
 
-      // adjust DelegationAccount counter
-      decrement(deps.storage, &DELEGATION_ACCOUNTS_LEN, 1)?;
+        if growth > x_deleg_growth || profit > x_deleg_profit {
+          return Err(ContractError::InsufficientDelegation {});
+        }
 
-      amount
+        // withdrawing everything that's left closes the account
+        if growth == x_deleg_growth && profit == x_deleg_profit {
+          withdraw_all(deps.storage, &mut account)?
+        } else {
+          (
+            account.withdraw_partial(deps.storage, growth, profit)?,
+            Uint128::zero(),
+          )
+        }
+      }
     } else {
-      Uint128::zero()
+      (Uint128::zero(), Uint128::zero())
     };
 
+  // withdrawn liquidity is either sent right away or starts unbonding, in
+  // which case only the profit is sent now. Unbonding liquidity stays in the
+  // house, so reservations are only checked once it's claimed.
+  let unbonding_period = UNBONDING_PERIOD.load(deps.storage)?;
+  let (amount, unbonding_amount) = if unbonding_period == 0 {
+    ensure_liquidity_unlocked(deps.storage)?;
+    (add(liquidity, profit, "withdrawal")?, Uint128::zero())
+  } else {
+    if !liquidity.is_zero() {
+      Unbonding::create(
+        deps.storage,
+        &info.sender,
+        liquidity,
+        env.block.time,
+        env.block.time.plus_seconds(unbonding_period),
+      )?;
+    }
+    (profit, liquidity)
+  };
+
   // build response with token transfer submsg
   let mut resp = Response::new().add_attributes(vec![
     attr("action", "withdraw"),
     attr("amount", amount.to_string()),
+    attr("unbonding_amount", unbonding_amount.to_string()),
   ]);
 
   if !amount.is_zero() {
@@ -60,3 +84,40 @@ pub fn withdraw(
 
   Ok(resp)
 }
+
+/// Process the DelegationAccount's outstanding delegation, claiming whatever
+/// liquidity and profit is owed, and remove the account.
+fn withdraw_all(
+  storage: &mut dyn Storage,
+  account: &mut DelegationAccount,
+) -> ContractResult<(Uint128, Uint128)> {
+  // total number oxisting delegation acounts:
+  let n_accounts = DelegationAccount::get_count(storage)?;
+
+  let (mut liquidity, mut profit) = account.withdraw(storage)?;
+
+  // the last account sweeps any leftover dust. Free liquidity is only dust
+  // once nothing is left in the growth pool, which the share token vault may
+  // still hold.
+  if n_accounts == 1 {
+    NET_PROFIT.update(storage, |dust| -> ContractResult<_> {
+      profit = add(profit, dust, "profit")?;
+      Ok(Uint128::zero())
+    })?;
+
+    if NET_GROWTH_DELEGATION.load(storage)?.is_zero() {
+      let dust = get_free_liquidity(storage)?;
+      liquidity = add(liquidity, dust, "liquidity")?;
+      decrement(storage, &NET_LIQUIDITY, dust)?;
+    }
+  }
+
+  // remove the account and its place in the memoization queue
+  DELEGATION_ACCOUNTS.remove(storage, account.owner.clone());
+  dequeue_memoization(storage, &account.owner)?;
+
+  // adjust DelegationAccount counter
+  decrement(storage, &DELEGATION_ACCOUNTS_LEN, 1)?;
+
+  Ok((liquidity, profit))
+}
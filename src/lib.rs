@@ -4,6 +4,9 @@ pub mod contract;
 #[cfg(not(feature = "library"))]
 pub mod execute;
 
+#[cfg(not(feature = "library"))]
+pub mod migrations;
+
 #[cfg(not(feature = "library"))]
 pub mod query;
 
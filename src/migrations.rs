@@ -0,0 +1,84 @@
+use crate::{
+  models::{Config, ContractResult, FeeConfig, PoolIndices, UnbondingPool},
+  msg::MigrateMsg,
+  state::{
+    enqueue_memoization, validate_config, validate_fee_config, CONFIG, DELEGATION_ACCOUNTS,
+    FEE_CONFIG, LIFETIME_FEES, MEMOIZATION_QUEUE_LEN, MEMOIZATION_QUEUE_TAIL, NET_FEES,
+    NET_LIQUIDITY_RESERVED, N_AMORTIZED, PAUSED_OPERATIONS, POOL_INDICES, SHARE_SUPPLY,
+    UNBONDINGS_SEQ_NO, UNBONDING_PERIOD, UNBONDING_POOL,
+  },
+  util::parse_version,
+};
+use cosmwasm_std::{Addr, DepsMut, Storage, Uint128};
+use cw_storage_plus::Deque;
+
+/// Memoization queue used by v0.0.3, which couldn't remove entries
+const LEGACY_MEMOIZATION_QUEUE: Deque<Addr> = Deque::new("memoization_queue");
+
+/// Upgrade state written by the given older contract version, applying any
+/// config values included in the MigrateMsg afterwards.
+pub fn migrate(
+  deps: DepsMut,
+  from_version: &str,
+  msg: &MigrateMsg,
+) -> ContractResult<()> {
+  let from_version = parse_version(from_version)?;
+
+  if from_version < (0, 0, 4) {
+    upgrade_v0_0_4(deps.storage)?;
+  }
+
+  if let Some(fee_config) = &msg.fee {
+    validate_fee_config(deps.api, fee_config)?;
+    FEE_CONFIG.save(deps.storage, fee_config)?;
+  }
+  if let Some(unbonding_period) = msg.unbonding_period {
+    UNBONDING_PERIOD.save(deps.storage, &unbonding_period)?;
+  }
+  if let Some(config) = &msg.config {
+    validate_config(config)?;
+    CONFIG.save(deps.storage, config)?;
+  }
+
+  Ok(())
+}
+
+/// Upgrade state written by v0.0.3. This initializes the config, fees,
+/// pausing, reservations, unbonding, share tokens and pool indices, and moves
+/// the memoization queue into its map-based replacement. Existing accounts
+/// load with zero indices and convert their Delegation records the next time
+/// they settle, either when they're next used or amortized.
+fn upgrade_v0_0_4(storage: &mut dyn Storage) -> ContractResult<()> {
+  CONFIG.save(storage, &Config::default())?;
+  PAUSED_OPERATIONS.save(storage, &vec![])?;
+  FEE_CONFIG.save(storage, &FeeConfig::default())?;
+  NET_FEES.save(storage, &Uint128::zero())?;
+  LIFETIME_FEES.save(storage, &Uint128::zero())?;
+
+  // the unused percentage stored here is replaced by the reserved amount,
+  // which starts at zero since no reservations exist yet
+  NET_LIQUIDITY_RESERVED.save(storage, &Uint128::zero())?;
+
+  UNBONDING_PERIOD.save(storage, &0)?;
+  UNBONDING_POOL.save(storage, &UnbondingPool::default())?;
+  UNBONDINGS_SEQ_NO.save(storage, &0)?;
+  SHARE_SUPPLY.save(storage, &Uint128::zero())?;
+
+  // snapshots are no longer taken, so nothing needs their sequence number
+  storage.remove(b"snapshot_seq_no");
+  POOL_INDICES.save(storage, &PoolIndices::default())?;
+
+  // drop queue entries for accounts that no longer exist along with any
+  // duplicates, which the legacy queue could hold
+  N_AMORTIZED.save(storage, &0)?;
+  MEMOIZATION_QUEUE_TAIL.save(storage, &0)?;
+  MEMOIZATION_QUEUE_LEN.save(storage, &0)?;
+
+  while let Some(owner) = LEGACY_MEMOIZATION_QUEUE.pop_front(storage)? {
+    if DELEGATION_ACCOUNTS.has(storage, owner.clone()) {
+      enqueue_memoization(storage, &owner)?;
+    }
+  }
+
+  Ok(())
+}
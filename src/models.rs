@@ -1,16 +1,21 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Order, Storage, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Order, Storage, Timestamp, Uint128};
 use cw_storage_plus::Map;
 
 use crate::{
   error::ContractError,
   state::{
-    DELEGATION_ACCOUNTS, DELEGATION_ACCOUNTS_LEN, GROWTH_DELEGATIONS, GROWTH_DELEGATIONS_SEQ_NO,
-    GROWTH_DELEGATOR_COUNT, NET_GROWTH_DELEGATION, NET_LIQUIDITY, NET_PROFIT,
-    NET_PROFIT_DELEGATION, PROFIT_DELEGATIONS, PROFIT_DELEGATIONS_SEQ_NO, PROFIT_DELEGATOR_COUNT,
-    SNAPSHOTS, SNAPSHOTS_INDEX, SNAPSHOTS_LEN, SNAPSHOT_SEQ_NO,
+    dequeue_memoization, enqueue_memoization, ensure_account_delegation_allowed,
+    ensure_delegation_allowed, get_free_liquidity, DELEGATION_ACCOUNTS, DELEGATION_ACCOUNTS_LEN,
+    GROWTH_DELEGATIONS, GROWTH_DELEGATIONS_SEQ_NO, GROWTH_DELEGATOR_COUNT, NET_GROWTH_DELEGATION,
+    NET_LIQUIDITY, NET_LIQUIDITY_RESERVED, NET_PROFIT, NET_PROFIT_DELEGATION, POOL_INDICES,
+    PROFIT_DELEGATIONS, PROFIT_DELEGATIONS_SEQ_NO, PROFIT_DELEGATOR_COUNT, RESERVATIONS, SNAPSHOTS,
+    SNAPSHOTS_INDEX, SNAPSHOTS_LEN, UNBONDINGS, UNBONDINGS_SEQ_NO, UNBONDING_POOL,
+  },
+  util::{
+    add, decrement, increment, mul_index, mul_index_ceil, mul_ratio, mul_ratio_ceil, sub, to_index,
+    to_index_ceil,
   },
-  util::{decrement, increment},
 };
 
 pub type ContractResult<T> = Result<T, ContractError>;
@@ -21,6 +26,19 @@ pub enum DelegationType {
   Profit,
 }
 
+/// Operations that can be paused by the house
+#[cw_serde]
+pub enum Operation {
+  Delegate,
+  Withdraw,
+  ReceivePayment,
+  SendPayment,
+  SendProfit,
+  ClaimFees,
+  /// Memoizing claims through Amortize or SettleAccount
+  Amortize,
+}
+
 #[cw_serde]
 pub struct DelegationAccount {
   pub owner: Addr,
@@ -28,6 +46,13 @@ pub struct DelegationAccount {
   pub memoized_profit: Uint128,
   pub memoized_growth: Uint128,
   pub memoized_loss: Uint128,
+  #[serde(default)]
+  pub growth_delegation: Uint128,
+  #[serde(default)]
+  pub profit_delegation: Uint128,
+  /// Pool indices as of the last time the account's claims were settled
+  #[serde(default)]
+  pub indices: PoolIndices,
 }
 
 #[cw_serde]
@@ -36,8 +61,166 @@ pub struct ClientAccount {
   pub created_at: Timestamp,
   pub amount_spent: Uint128,
   pub amount_received: Uint128,
+  pub limits: Option<ClientLimits>,
+  pub payout_window: Option<PayoutWindow>,
+  #[serde(default)]
+  pub status: ClientStatus,
+  #[serde(default)]
+  pub metadata: ClientMetadata,
+}
+
+/// Optional details describing a client, for display by frontends.
+#[cw_serde]
+#[derive(Default)]
+pub struct ClientMetadata {
+  pub name: Option<String>,
+  pub description: Option<String>,
+  /// Link to the client's website or app
+  pub url: Option<String>,
+  /// Free-form category for grouping clients, like "dice" or "lottery"
+  pub category: Option<String>,
+  /// URL of the client's logo image
+  pub logo: Option<String>,
+}
+
+/// Whether a client can currently send or receive payments through the house.
+/// Suspended clients keep their account and payment totals.
+#[cw_serde]
+pub enum ClientStatus {
+  Active,
+  Suspended,
+}
+
+impl Default for ClientStatus {
+  fn default() -> Self {
+    Self::Active
+  }
+}
+
+#[cw_serde]
+pub struct ClientLimits {
+  /// Max amount the client can pay out in a single payment
+  pub max_payout: Option<Uint128>,
+  /// Max amount the client can pay out within a window of time
+  pub max_window_payout: Option<PayoutWindowLimit>,
+  /// Max value of the client's amount spent minus amount received
+  pub max_net_loss: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct PayoutWindowLimit {
+  pub amount: Uint128,
+  pub seconds: u64,
+}
+
+/// Running total of payments made by a client in its current payout window.
+/// A new window starts with the first payment made after the last one ends.
+#[cw_serde]
+pub struct PayoutWindow {
+  pub started_at: Timestamp,
+  pub amount_spent: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct FeeConfig {
+  /// Portion of house revenue taken as a fee, in basis points
+  pub rate: u16,
+  /// Address that claimed fees are sent to, defaulting to the claimant
+  pub recipient: Option<Addr>,
+}
+
+/// House settings that can be changed with UpdateConfig
+#[cw_serde]
+pub struct Config {
+  /// Smallest amount that can be delegated at once
+  pub min_delegation: Uint128,
+  /// Cap on total house liquidity, beyond which new delegations are refused
+  pub max_liquidity: Option<Uint128>,
+  /// Cap on the total amount delegated by a single account
+  pub max_account_delegation: Option<Uint128>,
+  /// Max number of legacy snapshots returned by a single query. Deprecated
+  /// along with the Snapshots query, since no new snapshots are taken.
+  pub snapshot_query_limit: u32,
+  /// Number of accounts amortized as a side effect of other operations
+  pub amortization_batch_size: u32,
+  /// Max number of legacy snapshots processed per account while amortizing,
+  /// so that an account with a long backlog can't exhaust the gas of whatever
+  /// operation triggered amortization
+  #[serde(default = "default_amortization_snapshot_limit")]
+  pub amortization_snapshot_limit: u32,
+}
+
+fn default_amortization_snapshot_limit() -> u32 {
+  50
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      min_delegation: Uint128::zero(),
+      max_liquidity: None,
+      max_account_delegation: None,
+      snapshot_query_limit: 20,
+      amortization_batch_size: 1,
+      amortization_snapshot_limit: default_amortization_snapshot_limit(),
+    }
+  }
+}
+
+/// CW20 share token to instantiate for the house's growth pool vault
+#[cw_serde]
+pub struct ShareTokenConfig {
+  pub code_id: u64,
+  pub name: String,
+  pub symbol: String,
+  pub decimals: u8,
+}
+
+/// Liquidity set aside by a client for a payment whose amount isn't yet known.
+#[cw_serde]
+pub struct Reservation {
+  pub client: Addr,
+  pub amount: Uint128,
+  pub created_at: Timestamp,
+}
+
+/// Liquidity withdrawn by delegators that is waiting to mature. Each pending
+/// Unbonding owns a number of shares in the pool, which absorbs its pro-rata
+/// part of any loss incurred by the house in the meantime.
+#[cw_serde]
+#[derive(Default)]
+pub struct UnbondingPool {
+  pub epoch: u64,
+  pub shares: Uint128,
+  pub amount: Uint128,
 }
 
+#[cw_serde]
+pub struct Unbonding {
+  pub epoch: u64,
+  pub shares: Uint128,
+  pub created_at: Timestamp,
+  pub matures_at: Timestamp,
+}
+
+/// Cumulative amounts accrued per unit of delegation in each pool. An
+/// account is owed its delegation times the increase in each index since it
+/// last settled.
+#[cw_serde]
+#[derive(Default)]
+pub struct PoolIndices {
+  /// Growth per unit of growth delegation
+  pub growth: Decimal256,
+  /// Loss per unit of growth delegation
+  pub loss: Decimal256,
+  /// Profit per unit of profit delegation
+  pub profit: Decimal256,
+}
+
+/// Growth and loss recorded between delegation changes, before accounting
+/// moved to PoolIndices. No new snapshots are taken, and the remaining ones
+/// are removed as the legacy Delegation records spanning them are converted.
 #[cw_serde]
 pub struct Snapshot {
   pub seq_no: Uint128,
@@ -48,6 +231,8 @@ pub struct Snapshot {
   pub loss: Uint128,
 }
 
+/// Delegation record from before accounting moved to PoolIndices, converted
+/// into the account's delegation amounts the next time it settles.
 #[cw_serde]
 pub struct Delegation {
   pub owner: Addr,
@@ -68,6 +253,9 @@ impl DelegationAccount {
       memoized_growth: Uint128::zero(),
       memoized_loss: Uint128::zero(),
       memoized_profit: Uint128::zero(),
+      growth_delegation: Uint128::zero(),
+      profit_delegation: Uint128::zero(),
+      indices: PoolIndices::default(),
     }
   }
 
@@ -80,13 +268,15 @@ impl DelegationAccount {
     storage: &dyn Storage,
     target: DelegationType,
   ) -> ContractResult<bool> {
-    let seq_no_item = match target {
-      DelegationType::Growth => &GROWTH_DELEGATIONS_SEQ_NO,
-      DelegationType::Profit => &PROFIT_DELEGATIONS_SEQ_NO,
-    };
-    Ok(seq_no_item.may_load(storage, self.owner.clone())?.is_some())
+    let (x_deleg_growth, x_deleg_profit) = self.get_delegation_amounts(storage)?;
+    Ok(match target {
+      DelegationType::Growth => !x_deleg_growth.is_zero(),
+      DelegationType::Profit => !x_deleg_profit.is_zero(),
+    })
   }
 
+  /// Return the account's latest legacy Delegation record in the given pool,
+  /// if it hasn't been converted yet.
   pub fn get_latest_delegation(
     &self,
     storage: &dyn Storage,
@@ -106,70 +296,39 @@ impl DelegationAccount {
     Ok(None)
   }
 
+  /// Add to the account's delegation in the given pool. Claims are settled
+  /// up to now beforehand. Returns the new delegation amount.
   pub fn delegate(
-    &self,
+    &mut self,
     storage: &mut dyn Storage,
     target: DelegationType,
     delta: Uint128,
   ) -> ContractResult<Uint128> {
-    let (net_delegation_item, delegations_map, delegations_seq_no, delegator_count_item) =
-      match target {
-        DelegationType::Growth => (
-          &NET_GROWTH_DELEGATION,
-          &GROWTH_DELEGATIONS,
-          &GROWTH_DELEGATIONS_SEQ_NO,
-          &GROWTH_DELEGATOR_COUNT,
-        ),
-        DelegationType::Profit => (
-          &NET_PROFIT_DELEGATION,
-          &PROFIT_DELEGATIONS,
-          &PROFIT_DELEGATIONS_SEQ_NO,
-          &PROFIT_DELEGATOR_COUNT,
-        ),
-      };
-
-    increment(storage, net_delegation_item, delta)?;
-    increment(storage, &SNAPSHOT_SEQ_NO, Uint128::one())?;
-
-    let mut amount = delta.clone(); // new total delegation amount for the user
-    let mut i_next_deleg: u128 = 0;
-
-    // get the index of the next Snapshot to be made in the future
-    let i_next_snapshot = Snapshot::get_next_index(storage)?;
-
-    // if no new snapshots have been made since the last time the user updated their delegation
-    // simply increment the most recent past delegation created the user instead of creating
-    // an entirely new one.
-    if let Some((i_prev_deleg, mut prev_deleg)) = self.get_latest_delegation(storage, target)? {
-      // set the new delegation amount to the previous amount plus the delta
-      amount += prev_deleg.amount;
+    self.settle_claims(storage)?;
+
+    let (net_delegation_item, delegator_count_item, delegation) = match target {
+      DelegationType::Growth => (
+        &NET_GROWTH_DELEGATION,
+        &GROWTH_DELEGATOR_COUNT,
+        &mut self.growth_delegation,
+      ),
+      DelegationType::Profit => (
+        &NET_PROFIT_DELEGATION,
+        &PROFIT_DELEGATOR_COUNT,
+        &mut self.profit_delegation,
+      ),
+    };
 
-      if i_next_snapshot == prev_deleg.i_snapshot.into() {
-        prev_deleg.amount = amount;
-        delegations_map.save(storage, (self.owner.clone(), i_prev_deleg), &prev_deleg)?;
-        return Ok(prev_deleg.amount);
-      }
-      // otherwise...
-      // since we need to create a new Delegation, calculate its index for use
-      // in its delegations map key:
-      i_next_deleg = i_prev_deleg + 1;
-    } else {
+    if delegation.is_zero() && !delta.is_zero() {
       increment(storage, delegator_count_item, 1)?;
     }
 
-    // increment the delegation sequence number
-    delegations_seq_no.save(storage, self.owner.clone(), &i_next_deleg)?;
+    increment(storage, net_delegation_item, delta)?;
 
-    // insert the new Delegation
-    delegations_map.save(
-      storage,
-      (self.owner.clone(), i_next_deleg),
-      &Delegation {
-        owner: self.owner.clone(),
-        amount,
-        i_snapshot: i_next_snapshot.into(),
-      },
-    )?;
+    *delegation = add(*delegation, delta, "account.delegation")?;
+    let amount = *delegation;
+
+    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), self)?;
 
     Ok(amount)
   }
@@ -178,8 +337,10 @@ impl DelegationAccount {
     &mut self,
     storage: &mut dyn Storage,
   ) -> ContractResult<Uint128> {
-    // add memoized profit to total profit and clear the memoized value.
-    let mut amount = self.claim(storage, DelegationType::Profit, false)?.0 + self.memoized_profit;
+    self.settle_claims(storage)?;
+
+    // take all memoized profit and clear the memoized value.
+    let mut amount = self.memoized_profit;
 
     self.memoized_profit = Uint128::zero();
 
@@ -187,76 +348,443 @@ impl DelegationAccount {
     if !amount.is_zero() {
       NET_PROFIT.update(storage, |x| -> ContractResult<_> {
         amount = x.min(amount);
-        Ok(x - amount)
+        sub(x, amount, "net_profit")
       })?;
     }
 
-    // increase the snapshot seq no to signal that a new snapshot should be taken
-    // the next time the house sends or receives payment
-    increment(storage, &SNAPSHOT_SEQ_NO, Uint128::one())?;
-
     // save changes made to this account so far
     DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), &self)?;
 
     Ok(amount)
   }
 
-  pub fn withdraw(
-    &self,
+  /// Move claimable profit into the account's delegation in the given pool
+  /// without transferring any tokens. Profit not compounded remains memoized.
+  /// Returns the amount compounded.
+  pub fn compound(
+    &mut self,
     storage: &mut dyn Storage,
+    target: DelegationType,
+    amount: Option<Uint128>,
   ) -> ContractResult<Uint128> {
+    self.settle_claims(storage)?;
+
+    let profit = self.memoized_profit;
+    let requested_amount = amount.unwrap_or(profit);
+
+    if requested_amount > profit {
+      return Err(ContractError::InsufficientFunds {});
+    }
+
+    self.memoized_profit = sub(profit, requested_amount, "account.profit")?;
+
+    // move the profit out of the net profit accumulator into liquidity
+    let mut amount = requested_amount;
+    NET_PROFIT.update(storage, |x| -> ContractResult<_> {
+      amount = x.min(amount);
+      sub(x, amount, "net_profit")
+    })?;
+
+    if !amount.is_zero() {
+      // compounding adds liquidity just like a new delegation does
+      let (x_deleg_growth, x_deleg_profit) = self.get_delegation_amounts(storage)?;
+      ensure_delegation_allowed(
+        storage,
+        amount,
+        Some(add(x_deleg_growth, x_deleg_profit, "account.delegation")?),
+      )?;
+
+      increment(storage, &NET_LIQUIDITY, amount)?;
+      self.delegate(storage, target, amount)?;
+    }
+
+    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), &self)?;
+
+    Ok(amount)
+  }
+
+  /// Remove all of the account's delegations, returning the liquidity and
+  /// profit owed to it.
+  pub fn withdraw(
+    &mut self,
+    storage: &mut dyn Storage,
+  ) -> ContractResult<(Uint128, Uint128)> {
+    self.settle_claims(storage)?;
+
+    let x_deleg_growth = self.growth_delegation;
+    let x_deleg_profit = self.profit_delegation;
+
     // decrement delegator counts
-    if self.has_delegation(storage, DelegationType::Growth)? {
+    if !x_deleg_growth.is_zero() {
       decrement(storage, &GROWTH_DELEGATOR_COUNT, 1)?;
     }
-    if self.has_delegation(storage, DelegationType::Profit)? {
+    if !x_deleg_profit.is_zero() {
       decrement(storage, &PROFIT_DELEGATOR_COUNT, 1)?;
     }
 
-    increment(storage, &SNAPSHOT_SEQ_NO, Uint128::one())?;
-
     // compute the total amount delegated by the user
-    let (x_deleg_growth, x_deleg_profit) = self.get_delegation_amounts(storage)?;
-    let x_delegation = x_deleg_growth + x_deleg_profit;
+    let x_delegation = add(x_deleg_growth, x_deleg_profit, "account.delegation")?;
 
-    // compute user's total growth and loss in their share of the pool's overall liquidity
-    let (x_growth, x_loss) = self.claim(storage, DelegationType::Growth, false)?;
+    // all profit owed to the user has been memoized by settling
+    let mut profit_delta = self.memoized_profit;
 
-    // compute any unclaimed profit hanging around for the user
-    let mut profit_delta =
-      self.claim(storage, DelegationType::Profit, false)?.0 + self.memoized_profit;
+    // compute amount to subtract from global liquidity amount. rounding can
+    // leave a wiped out account's loss slightly above what it has.
+    let x_credit = add(x_delegation, self.memoized_growth, "account.growth")?;
+    let mut liquidity_delta = x_credit.saturating_sub(self.memoized_loss);
 
-    // compute amount to subtract from global liquidity amount
-    let mut liquidity_delta =
-      (x_delegation + x_growth + self.memoized_growth) - (x_loss + self.memoized_loss);
-
-    let mut balance =
-      (x_delegation + x_growth + self.memoized_growth) - (x_loss + self.memoized_loss);
+    let mut balance = liquidity_delta;
 
     NET_PROFIT.update(storage, |net_profit| -> ContractResult<_> {
       profit_delta = profit_delta.min(net_profit);
-      Ok(net_profit - profit_delta)
+      sub(net_profit, profit_delta, "net_profit")
     })?;
 
-    balance += profit_delta;
+    // liquidity held for pending unbondings doesn't belong to delegators
+    let unbonding_amount = UNBONDING_POOL.load(storage)?.amount;
 
     NET_LIQUIDITY.update(storage, |net_liquidity| -> ContractResult<_> {
-      if liquidity_delta > net_liquidity {
-        let overflow_amount = liquidity_delta - net_liquidity;
-        liquidity_delta -= overflow_amount;
-        balance -= overflow_amount;
+      let delegated_liquidity = net_liquidity.saturating_sub(unbonding_amount);
+      if liquidity_delta > delegated_liquidity {
+        let overflow_amount = liquidity_delta - delegated_liquidity;
+        liquidity_delta = delegated_liquidity;
+        balance = sub(balance, overflow_amount, "account.liquidity")?;
       }
-      Ok(net_liquidity - liquidity_delta)
+      sub(net_liquidity, liquidity_delta, "net_liquidity")
     })?;
 
     decrement(storage, &NET_GROWTH_DELEGATION, x_deleg_growth)?;
     decrement(storage, &NET_PROFIT_DELEGATION, x_deleg_profit)?;
 
-    // remove Delegations
-    self.remove_delegations(storage, DelegationType::Growth);
-    self.remove_delegations(storage, DelegationType::Profit);
+    self.growth_delegation = Uint128::zero();
+    self.profit_delegation = Uint128::zero();
+    self.memoized_growth = Uint128::zero();
+    self.memoized_loss = Uint128::zero();
+    self.memoized_profit = Uint128::zero();
+
+    Ok((balance, profit_delta))
+  }
+
+  /// Withdraw the given amounts from the account's growth and profit
+  /// delegations without closing the account. Claims are settled up to now
+  /// and kept in the account's memoized values. Returns the amount to send.
+  pub fn withdraw_partial(
+    &mut self,
+    storage: &mut dyn Storage,
+    growth: Uint128,
+    profit: Uint128,
+  ) -> ContractResult<Uint128> {
+    self.settle_claims(storage)?;
+
+    let (x_deleg_growth, x_deleg_profit) = self.get_delegation_amounts(storage)?;
+    if growth > x_deleg_growth || profit > x_deleg_profit {
+      return Err(ContractError::InsufficientDelegation {});
+    }
+
+    // the delegation left behind must still cover any outstanding loss
+    let amount = add(growth, profit, "withdrawal")?;
+    let x_credit = add(
+      add(x_deleg_growth, x_deleg_profit, "account.delegation")?,
+      self.memoized_growth,
+      "account.growth",
+    )?;
+    if x_credit < add(self.memoized_loss, amount, "account.loss")? {
+      return Err(ContractError::InsufficientDelegation {});
+    }
+
+    decrement(storage, &NET_LIQUIDITY, amount)?;
+
+    self.undelegate(storage, DelegationType::Growth, growth)?;
+    self.undelegate(storage, DelegationType::Profit, profit)?;
+
+    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), &self)?;
+
+    Ok(amount)
+  }
+
+  /// Move delegation between the account's growth and profit pools. Claims
+  /// are settled up to now beforehand.
+  pub fn rebalance(
+    &mut self,
+    storage: &mut dyn Storage,
+    growth_to_profit: Uint128,
+    profit_to_growth: Uint128,
+  ) -> ContractResult<()> {
+    self.settle_claims(storage)?;
+
+    let (x_deleg_growth, x_deleg_profit) = self.get_delegation_amounts(storage)?;
+    if growth_to_profit > x_deleg_growth || profit_to_growth > x_deleg_profit {
+      return Err(ContractError::InsufficientDelegation {});
+    }
+
+    self.undelegate(storage, DelegationType::Growth, growth_to_profit)?;
+    self.undelegate(storage, DelegationType::Profit, profit_to_growth)?;
+
+    if !growth_to_profit.is_zero() {
+      self.delegate(storage, DelegationType::Profit, growth_to_profit)?;
+    }
+    if !profit_to_growth.is_zero() {
+      self.delegate(storage, DelegationType::Growth, profit_to_growth)?;
+    }
+
+    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), &self)?;
+
+    Ok(())
+  }
+
+  /// Fold the account's settled growth and loss into its growth delegation,
+  /// so that the delegation alone is what the account is owed in liquidity.
+  /// Returns the resulting growth delegation.
+  pub fn restake_growth(
+    &mut self,
+    storage: &mut dyn Storage,
+  ) -> ContractResult<Uint128> {
+    self.settle_claims(storage)?;
+
+    if self.memoized_growth > self.memoized_loss {
+      self.delegate(
+        storage,
+        DelegationType::Growth,
+        sub(self.memoized_growth, self.memoized_loss, "account.growth")?,
+      )?;
+    } else if self.memoized_loss > self.memoized_growth {
+      // a loss can't take more than what's delegated
+      let (x_deleg_growth, _) = self.get_delegation_amounts(storage)?;
+      let net_loss =
+        sub(self.memoized_loss, self.memoized_growth, "account.loss")?.min(x_deleg_growth);
+      self.undelegate(storage, DelegationType::Growth, net_loss)?;
+    }
+
+    self.memoized_growth = Uint128::zero();
+    self.memoized_loss = Uint128::zero();
 
-    Ok(balance)
+    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), &self)?;
+
+    Ok(self.get_delegation_amounts(storage)?.0)
+  }
+
+  /// Compute the liquidity owed to the account's growth delegation, including
+  /// its unsettled growth and loss, without writing anything.
+  pub fn get_growth_value(
+    &self,
+    storage: &dyn Storage,
+  ) -> ContractResult<Uint128> {
+    let (x_deleg_growth, _) = self.get_delegation_amounts(storage)?;
+    let (growth, loss) = self.claim_readonly(storage, DelegationType::Growth)?;
+    let x_credit = add(
+      add(x_deleg_growth, growth, "account.growth")?,
+      self.memoized_growth,
+      "account.growth",
+    )?;
+    let x_debit = add(loss, self.memoized_loss, "account.loss")?;
+    Ok(x_credit.saturating_sub(x_debit))
+  }
+
+  /// Move the account to the recipient, returning the recipient's account.
+  /// Claims are settled up to now beforehand. If the recipient has no
+  /// account, the account is moved as-is. Otherwise, the delegations and
+  /// memoized values are merged into the recipient's existing account.
+  pub fn transfer(
+    &mut self,
+    storage: &mut dyn Storage,
+    recipient: &Addr,
+  ) -> ContractResult<DelegationAccount> {
+    self.settle_claims(storage)?;
+
+    let recipient_account = match DELEGATION_ACCOUNTS.may_load(storage, recipient.clone())? {
+      Some(mut recipient_account) => {
+        let x_deleg_growth = self.growth_delegation;
+        let x_deleg_profit = self.profit_delegation;
+
+        // a merge moves delegation that's already in the house, so only the
+        // recipient's cap applies
+        let (recipient_growth, recipient_profit) =
+          recipient_account.get_delegation_amounts(storage)?;
+        ensure_account_delegation_allowed(
+          storage,
+          add(x_deleg_growth, x_deleg_profit, "account.delegation")?,
+          add(recipient_growth, recipient_profit, "account.delegation")?,
+        )?;
+
+        self.undelegate(storage, DelegationType::Growth, x_deleg_growth)?;
+        self.undelegate(storage, DelegationType::Profit, x_deleg_profit)?;
+
+        if !x_deleg_growth.is_zero() {
+          recipient_account.delegate(storage, DelegationType::Growth, x_deleg_growth)?;
+        }
+        if !x_deleg_profit.is_zero() {
+          recipient_account.delegate(storage, DelegationType::Profit, x_deleg_profit)?;
+        }
+
+        recipient_account.memoized_growth = add(
+          recipient_account.memoized_growth,
+          self.memoized_growth,
+          "account.memoized_growth",
+        )?;
+        recipient_account.memoized_loss = add(
+          recipient_account.memoized_loss,
+          self.memoized_loss,
+          "account.memoized_loss",
+        )?;
+        recipient_account.memoized_profit = add(
+          recipient_account.memoized_profit,
+          self.memoized_profit,
+          "account.memoized_profit",
+        )?;
+
+        decrement(storage, &DELEGATION_ACCOUNTS_LEN, 1)?;
+
+        recipient_account
+      },
+      None => {
+        enqueue_memoization(storage, recipient)?;
+
+        DelegationAccount {
+          owner: recipient.clone(),
+          ..self.clone()
+        }
+      },
+    };
+
+    DELEGATION_ACCOUNTS.remove(storage, self.owner.clone());
+    dequeue_memoization(storage, &self.owner)?;
+
+    DELEGATION_ACCOUNTS.save(storage, recipient.clone(), &recipient_account)?;
+
+    Ok(recipient_account)
+  }
+
+  /// Claim everything owed to the account up to now in both pools, adding the
+  /// amounts to the account's memoized values.
+  pub fn settle_claims(
+    &mut self,
+    storage: &mut dyn Storage,
+  ) -> ContractResult<()> {
+    self.settle_claims_bounded(storage, None)?;
+    Ok(())
+  }
+
+  /// Like settle_claims, but process at most `max_snapshots` legacy snapshots,
+  /// leaving the rest for later calls. Returns the number of snapshots
+  /// processed and whether the account's legacy records are fully converted.
+  pub fn settle_claims_bounded(
+    &mut self,
+    storage: &mut dyn Storage,
+    max_snapshots: Option<u32>,
+  ) -> ContractResult<(u128, bool)> {
+    let result = self.settle_legacy_claims(storage, max_snapshots)?;
+
+    // unconverted legacy delegation accrues through the indices like the rest
+    let (x_deleg_growth, x_deleg_profit) = self.get_delegation_amounts(storage)?;
+    let indices = POOL_INDICES.load(storage)?;
+    let (growth, loss, profit) =
+      indices.get_accrued_amounts(&self.indices, x_deleg_growth, x_deleg_profit)?;
+
+    self.memoized_growth = add(self.memoized_growth, growth, "account.memoized_growth")?;
+    self.memoized_loss = add(self.memoized_loss, loss, "account.memoized_loss")?;
+    self.memoized_profit = add(self.memoized_profit, profit, "account.memoized_profit")?;
+    self.indices = indices;
+
+    Ok(result)
+  }
+
+  /// Convert the account's legacy Delegation records, memoizing what they
+  /// accrued across the snapshots they span and moving their amounts onto
+  /// the account. Accounts start out at zero indices, which is where the
+  /// indices began when snapshots stopped being taken, so nothing is counted
+  /// twice. A pool's records are only converted once all of their snapshots
+  /// have been processed. Returns the number of snapshots processed and
+  /// whether both pools were converted.
+  fn settle_legacy_claims(
+    &mut self,
+    storage: &mut dyn Storage,
+    max_snapshots: Option<u32>,
+  ) -> ContractResult<(u128, bool)> {
+    let mut n_snapshots: u128 = 0;
+    let mut is_settled = true;
+
+    for target in [DelegationType::Growth, DelegationType::Profit] {
+      let amount = match self.get_latest_delegation(storage, target.clone())? {
+        Some((_, deleg)) => deleg.amount,
+        None => continue,
+      };
+
+      let budget = max_snapshots.map(|max| (max as u128).saturating_sub(n_snapshots));
+      let (growth, loss, n_processed, is_done) =
+        self.claim_legacy(storage, target.clone(), budget)?;
+
+      n_snapshots = add(n_snapshots, n_processed, "account.n_snapshots")?;
+
+      match target {
+        DelegationType::Growth => {
+          self.memoized_growth = add(self.memoized_growth, growth, "account.memoized_growth")?;
+          self.memoized_loss = add(self.memoized_loss, loss, "account.memoized_loss")?;
+          if is_done {
+            self.growth_delegation = add(self.growth_delegation, amount, "account.delegation")?;
+          }
+        },
+        DelegationType::Profit => {
+          self.memoized_profit = add(self.memoized_profit, growth, "account.memoized_profit")?;
+          if is_done {
+            self.profit_delegation = add(self.profit_delegation, amount, "account.delegation")?;
+          }
+        },
+      }
+
+      if is_done {
+        self.remove_delegations(storage, target);
+      } else {
+        is_settled = false;
+      }
+    }
+
+    Ok((n_snapshots, is_settled))
+  }
+
+  /// Reduce the account's delegation in the given pool. Claims are settled
+  /// up to now beforehand. Returns the remaining delegation amount.
+  fn undelegate(
+    &mut self,
+    storage: &mut dyn Storage,
+    target: DelegationType,
+    delta: Uint128,
+  ) -> ContractResult<Uint128> {
+    if delta.is_zero() {
+      return Ok(Uint128::zero());
+    }
+
+    self.settle_claims(storage)?;
+
+    let (net_delegation_item, delegator_count_item, delegation) = match target {
+      DelegationType::Growth => (
+        &NET_GROWTH_DELEGATION,
+        &GROWTH_DELEGATOR_COUNT,
+        &mut self.growth_delegation,
+      ),
+      DelegationType::Profit => (
+        &NET_PROFIT_DELEGATION,
+        &PROFIT_DELEGATOR_COUNT,
+        &mut self.profit_delegation,
+      ),
+    };
+
+    if delta > *delegation {
+      return Err(ContractError::InsufficientDelegation {});
+    }
+
+    decrement(storage, net_delegation_item, delta)?;
+
+    *delegation = sub(*delegation, delta, "account.delegation")?;
+    let amount = *delegation;
+
+    // drop out of the pool entirely once nothing is left in it
+    if amount.is_zero() {
+      decrement(storage, delegator_count_item, 1)?;
+    }
+
+    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), self)?;
+
+    Ok(amount)
   }
 
   fn remove_delegations(
@@ -282,78 +810,92 @@ impl DelegationAccount {
     }
   }
 
+  /// Return the account's growth and profit delegation amounts, including
+  /// those of any legacy Delegation records not yet converted.
   pub fn get_delegation_amounts(
     &self,
     storage: &dyn Storage,
   ) -> ContractResult<(Uint128, Uint128)> {
+    let legacy_amount = |target| -> ContractResult<Uint128> {
+      Ok(match self.get_latest_delegation(storage, target)? {
+        Some((_, deleg)) => deleg.amount,
+        None => Uint128::zero(),
+      })
+    };
     Ok((
-      if let Some((_, deleg)) = self.get_latest_delegation(storage, DelegationType::Growth)? {
-        deleg.amount
-      } else {
-        Uint128::zero()
-      },
-      if let Some((_, deleg)) = self.get_latest_delegation(storage, DelegationType::Profit)? {
-        deleg.amount
-      } else {
-        Uint128::zero()
-      },
+      add(
+        self.growth_delegation,
+        legacy_amount(DelegationType::Growth)?,
+        "account.delegation",
+      )?,
+      add(
+        self.profit_delegation,
+        legacy_amount(DelegationType::Profit)?,
+        "account.delegation",
+      )?,
     ))
   }
 
-  pub fn claim(
+  /// Process the snapshots spanned by the account's legacy Delegation records
+  /// in the given pool, up to `max_snapshots` of them, returning the growth
+  /// and loss (or profit, for the profit pool) they accrued, the number of
+  /// snapshots processed and whether all of them were. Records whose span is
+  /// processed are removed, except the latest, which holds the delegation
+  /// amount. When the budget runs out, the current record's `i_snapshot` is
+  /// advanced past what was processed, so the next call resumes from there.
+  fn claim_legacy(
     &self,
     storage: &mut dyn Storage,
     target: DelegationType,
-    is_amortizing: bool,
-  ) -> ContractResult<(Uint128, Uint128)> {
+    max_snapshots: Option<u128>,
+  ) -> ContractResult<(Uint128, Uint128, u128, bool)> {
     let delegations_map = match target {
       DelegationType::Growth => &GROWTH_DELEGATIONS,
       DelegationType::Profit => &PROFIT_DELEGATIONS,
     };
 
     let delegations = self.load_delegations(storage, &delegations_map)?;
-
-    if delegations.is_empty() {
-      return Ok((Uint128::zero(), Uint128::zero()));
-    }
+    // SNAPSHOTS_INDEX is the index the next snapshot would have had
+    let i_end_snapshot = SNAPSHOTS_INDEX.load(storage)?.u128();
 
     let mut total_growth = Uint128::zero();
     let mut total_loss = Uint128::zero();
+    let mut n_snapshots: u128 = 0;
+
+    for (i, (seq_no, d0)) in delegations.iter().enumerate() {
+      let maybe_d1 = delegations.get(i + 1).map(|(_, d1)| d1);
+      let i_start = d0.i_snapshot.u128();
+      let i_end = maybe_d1
+        .map_or(i_end_snapshot, |d1| d1.i_snapshot.u128())
+        .max(i_start);
+      let i_stop = match max_snapshots {
+        Some(max) => i_end.min(i_start.saturating_add(max.saturating_sub(n_snapshots))),
+        None => i_end,
+      };
 
-    if delegations.len() > 1 {
-      for i in 0..delegations.len() - 1 {
-        let (d0_index, d0) = &delegations[i];
-        let d1 = &delegations[i + 1].1;
-        if d0.i_snapshot < d1.i_snapshot {
-          let (growth, loss) = self.process_delegation(storage, target.clone(), d0, Some(&d1))?;
-          total_growth += growth;
-          total_loss += loss;
-        }
-        delegations_map.remove(storage, (self.owner.clone(), *d0_index));
-      }
-    }
+      let (growth, loss) = self.process_delegation(storage, target.clone(), d0, i_stop)?;
+      total_growth = add(total_growth, growth, "account.growth")?;
+      total_loss = add(total_loss, loss, "account.loss")?;
+      n_snapshots = add(n_snapshots, i_stop - i_start, "account.n_snapshots")?;
 
-    // we only process the final delegation record if this claim call isn't
-    // happening as a result of calling amortize
-    if !is_amortizing {
-      if let Some((d0_index, d0)) = delegations.last() {
-        let (growth, loss) = self.process_delegation(storage, target.clone(), d0, None)?;
-        total_growth += growth;
-        total_loss += loss;
-        let i_next_snapshot = Snapshot::get_next_index(storage)?;
-        delegations_map.update(
+      if i_stop < i_end {
+        delegations_map.save(
           storage,
-          (self.owner.clone(), *d0_index),
-          |maybe_d0| -> ContractResult<_> {
-            let mut d0 = maybe_d0.unwrap();
-            d0.i_snapshot = Uint128::from(i_next_snapshot);
-            Ok(d0)
+          (self.owner.clone(), *seq_no),
+          &Delegation {
+            i_snapshot: i_stop.into(),
+            ..d0.clone()
           },
         )?;
+        return Ok((total_growth, total_loss, n_snapshots, false));
+      }
+
+      if maybe_d1.is_some() {
+        delegations_map.remove(storage, (self.owner.clone(), *seq_no));
       }
     }
 
-    Ok((total_growth, total_loss))
+    Ok((total_growth, total_loss, n_snapshots, true))
   }
 
   fn load_delegations(
@@ -375,14 +917,8 @@ impl DelegationAccount {
     storage: &mut dyn Storage,
     target: DelegationType,
     d0: &Delegation,
-    maybe_d1: Option<&Delegation>,
+    i_end: u128,
   ) -> ContractResult<(Uint128, Uint128)> {
-    let d1_snapshot_index = if let Some(d1) = maybe_d1 {
-      d1.i_snapshot.u128()
-    } else {
-      SNAPSHOTS_INDEX.load(storage)?.u128() + 1
-    };
-
     let mut stale_snapshot_indices: Vec<u128> = vec![];
     let mut updated_snapshots: Vec<(u128, Snapshot)> = vec![];
 
@@ -390,13 +926,13 @@ impl DelegationAccount {
       DelegationType::Growth => {
         let mut total_growth = Uint128::zero();
         let mut total_loss = Uint128::zero();
-        for i_snapshot in d0.i_snapshot.u128()..d1_snapshot_index {
+        for i_snapshot in d0.i_snapshot.u128()..i_end {
           if let Some(mut s) = SNAPSHOTS.may_load(storage, i_snapshot)? {
-            let x_total = s.get_total_delegation();
-            total_growth += s.growth.multiply_ratio(d0.amount, x_total);
-            total_loss += s.loss.multiply_ratio(d0.amount, s.growth_delegation);
+            let (growth, loss) = s.get_claim_amounts(&target, d0.amount)?;
+            total_growth = add(total_growth, growth, "account.growth")?;
+            total_loss = add(total_loss, loss, "account.loss")?;
 
-            s.claims_remaining -= 1;
+            s.claims_remaining = sub(s.claims_remaining, 1, "snapshot.claims_remaining")?;
             if s.claims_remaining == 0 {
               stale_snapshot_indices.push(i_snapshot);
             } else {
@@ -408,11 +944,12 @@ impl DelegationAccount {
       },
       DelegationType::Profit => {
         let mut total_growth = Uint128::zero();
-        for i_snapshot in d0.i_snapshot.u128()..d1_snapshot_index {
+        for i_snapshot in d0.i_snapshot.u128()..i_end {
           if let Some(mut s) = SNAPSHOTS.may_load(storage, i_snapshot)? {
-            total_growth += s.growth.multiply_ratio(d0.amount, s.get_total_delegation());
+            let (growth, _) = s.get_claim_amounts(&target, d0.amount)?;
+            total_growth = add(total_growth, growth, "account.profit")?;
 
-            s.claims_remaining -= 1;
+            s.claims_remaining = sub(s.claims_remaining, 1, "snapshot.claims_remaining")?;
             if s.claims_remaining == 0 {
               stale_snapshot_indices.push(i_snapshot);
             } else {
@@ -437,10 +974,40 @@ impl DelegationAccount {
     Ok(amounts)
   }
 
+  /// Compute the growth and loss (or profit, for the profit pool) owed to the
+  /// account in the given pool since it last settled, without writing
+  /// anything.
   pub fn claim_readonly(
     &self,
     storage: &dyn Storage,
     target: DelegationType,
+  ) -> ContractResult<(Uint128, Uint128)> {
+    let (legacy_growth, legacy_loss) = self.claim_legacy_readonly(storage, target.clone())?;
+    let (x_deleg_growth, x_deleg_profit) = self.get_delegation_amounts(storage)?;
+    let (growth, loss, profit) = POOL_INDICES.load(storage)?.get_accrued_amounts(
+      &self.indices,
+      x_deleg_growth,
+      x_deleg_profit,
+    )?;
+
+    Ok(match target {
+      DelegationType::Growth => (
+        add(legacy_growth, growth, "account.growth")?,
+        add(legacy_loss, loss, "account.loss")?,
+      ),
+      DelegationType::Profit => (
+        add(legacy_growth, profit, "account.profit")?,
+        Uint128::zero(),
+      ),
+    })
+  }
+
+  /// Compute what the account's legacy Delegation records in the given pool
+  /// have accrued, without writing anything.
+  fn claim_legacy_readonly(
+    &self,
+    storage: &dyn Storage,
+    target: DelegationType,
   ) -> ContractResult<(Uint128, Uint128)> {
     let delegations_map = match target {
       DelegationType::Growth => &GROWTH_DELEGATIONS,
@@ -463,22 +1030,22 @@ impl DelegationAccount {
         if d0.i_snapshot < d1.i_snapshot {
           let (growth, loss) =
             self.process_delegation_readonly(storage, target.clone(), d0, Some(&d1))?;
-          total_growth += growth;
-          total_loss += loss;
+          total_growth = add(total_growth, growth, "account.growth")?;
+          total_loss = add(total_loss, loss, "account.loss")?;
         }
       }
     }
 
     if let Some((_, d0)) = delegations.last() {
       let (growth, loss) = self.process_delegation_readonly(storage, target.clone(), d0, None)?;
-      total_growth += growth;
-      total_loss += loss;
+      total_growth = add(total_growth, growth, "account.growth")?;
+      total_loss = add(total_loss, loss, "account.loss")?;
     }
 
     Ok((total_growth, total_loss))
   }
 
-  fn process_delegation_readonly(
+  pub fn process_delegation_readonly(
     &self,
     storage: &dyn Storage,
     target: DelegationType,
@@ -488,7 +1055,7 @@ impl DelegationAccount {
     let d1_snapshot_index = if let Some(d1) = maybe_d1 {
       d1.i_snapshot.u128()
     } else {
-      SNAPSHOTS_INDEX.load(storage)?.u128() + 1
+      SNAPSHOTS_INDEX.load(storage)?.u128()
     };
 
     let amounts = match target {
@@ -497,9 +1064,9 @@ impl DelegationAccount {
         let mut total_loss = Uint128::zero();
         for i_snapshot in d0.i_snapshot.u128()..d1_snapshot_index {
           if let Some(s) = SNAPSHOTS.may_load(storage, i_snapshot)? {
-            let x_total = s.get_total_delegation();
-            total_growth += s.growth.multiply_ratio(d0.amount, x_total);
-            total_loss += s.loss.multiply_ratio(d0.amount, s.growth_delegation);
+            let (growth, loss) = s.get_claim_amounts(&target, d0.amount)?;
+            total_growth = add(total_growth, growth, "account.growth")?;
+            total_loss = add(total_loss, loss, "account.loss")?;
           }
         }
         (total_growth, total_loss)
@@ -508,7 +1075,8 @@ impl DelegationAccount {
         let mut total_growth = Uint128::zero();
         for i_snapshot in d0.i_snapshot.u128()..d1_snapshot_index {
           if let Some(s) = SNAPSHOTS.may_load(storage, i_snapshot)? {
-            total_growth += s.growth.multiply_ratio(d0.amount, s.get_total_delegation());
+            let (growth, _) = s.get_claim_amounts(&target, d0.amount)?;
+            total_growth = add(total_growth, growth, "account.profit")?;
           }
         }
         (total_growth, Uint128::zero())
@@ -518,89 +1086,404 @@ impl DelegationAccount {
     Ok(amounts)
   }
 
+  /// Compute the growth, loss and profit owed to the account, including what
+  /// has already been memoized, without writing anything.
+  pub fn get_claimable_amounts(
+    &self,
+    storage: &dyn Storage,
+  ) -> ContractResult<(Uint128, Uint128, Uint128)> {
+    let (growth, loss) = self.claim_readonly(storage, DelegationType::Growth)?;
+    let profit = self.claim_readonly(storage, DelegationType::Profit)?.0;
+    Ok((
+      add(growth, self.memoized_growth, "account.growth")?,
+      add(loss, self.memoized_loss, "account.loss")?,
+      add(profit, self.memoized_profit, "account.profit")?,
+    ))
+  }
+
+  /// Settle the account's claims, processing at most `max_snapshots` legacy
+  /// snapshots, and save it.
   pub fn memoize_claim_amounts(
     &mut self,
     storage: &mut dyn Storage,
+    max_snapshots: Option<u32>,
   ) -> ContractResult<()> {
-    let (growth, loss) = self.claim(storage, DelegationType::Growth, true)?;
-    let profit = self.claim(storage, DelegationType::Profit, true)?.0;
+    self.settle_claims_bounded(storage, max_snapshots)?;
+    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), self)?;
+    Ok(())
+  }
+}
 
-    self.memoized_growth += growth;
-    self.memoized_loss += loss;
-    self.memoized_profit += profit;
+impl FeeConfig {
+  /// Compute the fee owed on the given revenue amount.
+  pub fn get_fee(
+    &self,
+    revenue: Uint128,
+  ) -> Uint128 {
+    revenue.multiply_ratio(self.rate as u128, 10_000u128)
+  }
+}
+
+impl ClientAccount {
+  pub fn new(
+    owner: &Addr,
+    created_at: Timestamp,
+  ) -> Self {
+    Self {
+      owner: owner.clone(),
+      created_at,
+      amount_spent: Uint128::zero(),
+      amount_received: Uint128::zero(),
+      limits: None,
+      payout_window: None,
+      status: ClientStatus::Active,
+      metadata: ClientMetadata::default(),
+    }
+  }
+
+  /// Return an error if the client has been suspended.
+  pub fn ensure_active(&self) -> ContractResult<()> {
+    match self.status {
+      ClientStatus::Active => Ok(()),
+      ClientStatus::Suspended => Err(ContractError::ClientSuspended {}),
+    }
+  }
+
+  /// Tally a payment sent by the house on behalf of the client, enforcing
+  /// the client's payout limits.
+  pub fn spend(
+    &mut self,
+    time: Timestamp,
+    amount: Uint128,
+  ) -> ContractResult<()> {
+    if let Some(limits) = self.limits.clone() {
+      if let Some(max_payout) = limits.max_payout {
+        if amount > max_payout {
+          return Err(ContractError::PayoutLimitExceeded {});
+        }
+      }
+
+      if let Some(window_limit) = limits.max_window_payout {
+        // start a new window if the current one has elapsed
+        let window = match self.payout_window.take() {
+          Some(window) if time < window.started_at.plus_seconds(window_limit.seconds) => window,
+          _ => PayoutWindow {
+            started_at: time,
+            amount_spent: Uint128::zero(),
+          },
+        };
+        let amount_spent = add(window.amount_spent, amount, "payout_window.amount_spent")?;
+        if amount_spent > window_limit.amount {
+          return Err(ContractError::WindowPayoutLimitExceeded {});
+        }
+        self.payout_window = Some(PayoutWindow {
+          started_at: window.started_at,
+          amount_spent,
+        });
+      }
+
+      if let Some(max_net_loss) = limits.max_net_loss {
+        let net_loss = add(self.amount_spent, amount, "client.amount_spent")?
+          .saturating_sub(self.amount_received);
+        if net_loss > max_net_loss {
+          return Err(ContractError::NetLossLimitExceeded {});
+        }
+      }
+    }
+
+    self.amount_spent = add(self.amount_spent, amount, "client.amount_spent")?;
 
-    DELEGATION_ACCOUNTS.save(storage, self.owner.clone(), self)?;
     Ok(())
   }
 }
 
-impl Snapshot {
-  pub fn get_latest(storage: &mut dyn Storage) -> ContractResult<Option<(u128, Self)>> {
-    if SNAPSHOTS_LEN.load(storage)? == 0 {
-      return Ok(None);
+impl Reservation {
+  /// Reserve the given amount of free liquidity for the client.
+  pub fn create(
+    storage: &mut dyn Storage,
+    client: &Addr,
+    id: &String,
+    amount: Uint128,
+    created_at: Timestamp,
+  ) -> ContractResult<Self> {
+    if RESERVATIONS.has(storage, (client.clone(), id.clone())) {
+      return Err(ContractError::ReservationExists {});
     }
-    let idx = SNAPSHOTS_INDEX.load(storage)?.u128() - 1;
-    Ok(Some((idx, SNAPSHOTS.load(storage, idx)?)))
+    if amount > get_free_liquidity(storage)? {
+      return Err(ContractError::InsufficientLiquidity {});
+    }
+
+    let reservation = Self {
+      client: client.clone(),
+      amount,
+      created_at,
+    };
+
+    RESERVATIONS.save(storage, (client.clone(), id.clone()), &reservation)?;
+    increment(storage, &NET_LIQUIDITY_RESERVED, amount)?;
+
+    Ok(reservation)
   }
 
-  pub fn get_next_index(storage: &dyn Storage) -> ContractResult<u128> {
-    Ok(SNAPSHOTS_INDEX.load(storage)?.u128())
+  /// Remove the client's reservation, returning its liquidity to the pool.
+  pub fn release(
+    storage: &mut dyn Storage,
+    client: &Addr,
+    id: &String,
+  ) -> ContractResult<Self> {
+    let key = (client.clone(), id.clone());
+    let reservation = RESERVATIONS
+      .may_load(storage, key.clone())?
+      .ok_or(ContractError::NotFound {})?;
+
+    RESERVATIONS.remove(storage, key);
+    decrement(storage, &NET_LIQUIDITY_RESERVED, reservation.amount)?;
+
+    Ok(reservation)
   }
-  pub fn get_count(storage: &mut dyn Storage) -> ContractResult<u32> {
-    Ok(SNAPSHOTS_LEN.load(storage)?)
+}
+
+impl UnbondingPool {
+  /// Return the amount currently owed to the given unbonding.
+  pub fn get_amount(
+    &self,
+    unbonding: &Unbonding,
+  ) -> Uint128 {
+    if unbonding.epoch != self.epoch || self.shares.is_zero() {
+      Uint128::zero()
+    } else {
+      unbonding.shares.multiply_ratio(self.amount, self.shares)
+    }
   }
 
-  pub fn get_next_index_and_increment(storage: &mut dyn Storage) -> ContractResult<u128> {
-    Ok(
-      SNAPSHOTS_INDEX
-        .update(storage, |x| -> ContractResult<_> { Ok(x + Uint128::one()) })?
-        .u128()
-        - 1,
-    )
+  /// Charge the pool its share of a loss incurred by the house, in proportion
+  /// to the size of the pool relative to the growth delegation that absorbs
+  /// the rest. Returns the amount of loss absorbed by the pool.
+  pub fn absorb_loss(
+    storage: &mut dyn Storage,
+    loss: Uint128,
+  ) -> ContractResult<Uint128> {
+    let mut pool = UNBONDING_POOL.load(storage)?;
+    if pool.amount.is_zero() {
+      return Ok(Uint128::zero());
+    }
+
+    let net_growth_delegation = NET_GROWTH_DELEGATION.load(storage)?;
+    let pool_loss = mul_ratio(
+      loss,
+      pool.amount,
+      add(pool.amount, net_growth_delegation, "unbonding_pool.amount")?,
+      "unbonding_pool.loss",
+    )?
+    .min(pool.amount);
+
+    pool.amount = sub(pool.amount, pool_loss, "unbonding_pool.amount")?;
+    UNBONDING_POOL.save(storage, &pool)?;
+
+    Ok(pool_loss)
   }
+}
 
-  pub fn get_total_delegation(&self) -> Uint128 {
-    self.growth_delegation + self.profit_delegation
+impl Unbonding {
+  /// Move the given amount of liquidity into the unbonding pool on behalf of
+  /// the owner. The liquidity stays in the house until the unbonding matures.
+  pub fn create(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    amount: Uint128,
+    created_at: Timestamp,
+    matures_at: Timestamp,
+  ) -> ContractResult<Self> {
+    let mut pool = UNBONDING_POOL.load(storage)?;
+
+    // if losses have wiped out the pool, outstanding shares are worthless, so
+    // start a new epoch instead of pricing new shares against nothing.
+    if pool.amount.is_zero() && !pool.shares.is_zero() {
+      pool = UnbondingPool {
+        epoch: pool.epoch + 1,
+        shares: Uint128::zero(),
+        amount: Uint128::zero(),
+      };
+    }
+
+    let shares = if pool.shares.is_zero() {
+      amount
+    } else {
+      mul_ratio(amount, pool.shares, pool.amount, "unbonding_pool.shares")?
+    };
+
+    pool.shares = add(pool.shares, shares, "unbonding_pool.shares")?;
+    pool.amount = add(pool.amount, amount, "unbonding_pool.amount")?;
+
+    let unbonding = Self {
+      epoch: pool.epoch,
+      shares,
+      created_at,
+      matures_at,
+    };
+
+    let id = UNBONDINGS_SEQ_NO.update(storage, |n| -> ContractResult<_> { Ok(n + 1) })?;
+
+    UNBONDINGS.save(storage, (owner.clone(), id), &unbonding)?;
+    UNBONDING_POOL.save(storage, &pool)?;
+    increment(storage, &NET_LIQUIDITY, amount)?;
+
+    Ok(unbonding)
   }
 
-  pub fn upsert(
+  /// Remove all of the owner's matured unbondings from the pool, returning
+  /// the total amount owed.
+  pub fn claim_matured(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    time: Timestamp,
+  ) -> ContractResult<Uint128> {
+    let mut pool = UNBONDING_POOL.load(storage)?;
+    let mut total_amount = Uint128::zero();
+
+    let matured: Vec<(u64, Unbonding)> = UNBONDINGS
+      .prefix(owner.clone())
+      .range(storage, None, None, Order::Ascending)
+      .map(|r| r.unwrap())
+      .filter(|(_, unbonding)| unbonding.matures_at <= time)
+      .collect();
+
+    for (id, unbonding) in matured.iter() {
+      if unbonding.epoch == pool.epoch {
+        let amount = pool.get_amount(unbonding);
+        pool.shares = sub(pool.shares, unbonding.shares, "unbonding_pool.shares")?;
+        pool.amount = sub(pool.amount, amount, "unbonding_pool.amount")?;
+        total_amount = add(total_amount, amount, "unbonding.amount")?;
+      }
+      UNBONDINGS.remove(storage, (owner.clone(), *id));
+    }
+
+    // the last share out sweeps any rounding dust
+    if pool.shares.is_zero() {
+      total_amount = add(total_amount, pool.amount, "unbonding.amount")?;
+      pool.amount = Uint128::zero();
+    }
+
+    UNBONDING_POOL.save(storage, &pool)?;
+
+    if !total_amount.is_zero() {
+      decrement(storage, &NET_LIQUIDITY, total_amount)?;
+    }
+
+    Ok(total_amount)
+  }
+}
+
+impl PoolIndices {
+  /// Credit revenue added to the growth and profit pools to the delegators in
+  /// each. Revenue added to an empty pool can't be credited to anyone.
+  pub fn accrue_revenue(
     storage: &mut dyn Storage,
     growth: Uint128,
+    profit: Uint128,
+  ) -> ContractResult<Self> {
+    let mut indices = POOL_INDICES.load(storage)?;
+    let net_growth_delegation = NET_GROWTH_DELEGATION.load(storage)?;
+    let net_profit_delegation = NET_PROFIT_DELEGATION.load(storage)?;
+
+    if !growth.is_zero() && !net_growth_delegation.is_zero() {
+      let delta = to_index(growth, net_growth_delegation, "net_growth_delegation")?;
+      indices.growth = add(indices.growth, delta, "pool_indices.growth")?;
+    }
+    if !profit.is_zero() && !net_profit_delegation.is_zero() {
+      let delta = to_index(profit, net_profit_delegation, "net_profit_delegation")?;
+      indices.profit = add(indices.profit, delta, "pool_indices.profit")?;
+    }
+
+    POOL_INDICES.save(storage, &indices)?;
+    Ok(indices)
+  }
+
+  /// Charge a loss absorbed by the growth pool to its delegators. Unlike
+  /// revenue, losses round up, so that delegators are never charged less than
+  /// the house actually lost.
+  pub fn accrue_loss(
+    storage: &mut dyn Storage,
     loss: Uint128,
   ) -> ContractResult<Self> {
-    let seq_no = SNAPSHOT_SEQ_NO.load(storage)?;
-
-    // try to update the latest existing snapshot
-    if let Some((i_prev_snapshot, mut prev_snapshot)) = Self::get_latest(storage)? {
-      if prev_snapshot.seq_no == seq_no {
-        prev_snapshot.growth += growth;
-        prev_snapshot.loss += loss;
-        SNAPSHOTS.save(storage, i_prev_snapshot, &prev_snapshot)?;
-        return Ok(prev_snapshot);
-      }
+    let mut indices = POOL_INDICES.load(storage)?;
+    let net_growth_delegation = NET_GROWTH_DELEGATION.load(storage)?;
+
+    if !loss.is_zero() && !net_growth_delegation.is_zero() {
+      let delta = to_index_ceil(loss, net_growth_delegation, "net_growth_delegation")?;
+      indices.loss = add(indices.loss, delta, "pool_indices.loss")?;
     }
 
-    let i_snapshot = Self::get_next_index_and_increment(storage)?;
-    let growth_delegation = NET_GROWTH_DELEGATION.load(storage)?;
-    let profit_delegation = NET_PROFIT_DELEGATION.load(storage)?;
-    let claims_remaining =
-      GROWTH_DELEGATOR_COUNT.load(storage)? + PROFIT_DELEGATOR_COUNT.load(storage)?;
-
-    // if we didn't just end up updating the previous snapshot,
-    // we create and return a new one...
-    let snapshot = Snapshot {
-      seq_no,
-      claims_remaining,
-      growth_delegation,
-      profit_delegation,
-      growth,
-      loss,
-    };
+    POOL_INDICES.save(storage, &indices)?;
+    Ok(indices)
+  }
 
-    SNAPSHOTS.save(storage, i_snapshot, &snapshot)?;
+  /// Compute the growth, loss and profit accrued by the given delegation
+  /// amounts between the previous indices and these. Growth and profit round
+  /// down, and losses round up.
+  pub fn get_accrued_amounts(
+    &self,
+    prev: &Self,
+    growth_delegation: Uint128,
+    profit_delegation: Uint128,
+  ) -> ContractResult<(Uint128, Uint128, Uint128)> {
+    Ok((
+      mul_index(
+        growth_delegation,
+        sub(self.growth, prev.growth, "pool_indices.growth")?,
+        "account.growth",
+      )?,
+      mul_index_ceil(
+        growth_delegation,
+        sub(self.loss, prev.loss, "pool_indices.loss")?,
+        "account.loss",
+      )?,
+      mul_index(
+        profit_delegation,
+        sub(self.profit, prev.profit, "pool_indices.profit")?,
+        "account.profit",
+      )?,
+    ))
+  }
+}
 
-    increment(storage, &SNAPSHOTS_LEN, 1)?;
+impl Snapshot {
+  pub fn get_total_delegation(&self) -> ContractResult<Uint128> {
+    add(
+      self.growth_delegation,
+      self.profit_delegation,
+      "snapshot.total_delegation",
+    )
+  }
 
-    Ok(snapshot)
+  /// Compute the growth and loss owed by the snapshot to a delegation of the
+  /// given amount in the target pool. Nothing is owed out of an empty pool.
+  /// Like index-based claims, growth rounds down and losses round up.
+  pub fn get_claim_amounts(
+    &self,
+    target: &DelegationType,
+    amount: Uint128,
+  ) -> ContractResult<(Uint128, Uint128)> {
+    let growth = if self.growth.is_zero() {
+      Uint128::zero()
+    } else {
+      mul_ratio(
+        self.growth,
+        amount,
+        self.get_total_delegation()?,
+        "snapshot.total_delegation",
+      )?
+    };
+    let loss = match target {
+      DelegationType::Profit => Uint128::zero(),
+      DelegationType::Growth if self.loss.is_zero() => Uint128::zero(),
+      DelegationType::Growth => mul_ratio_ceil(
+        self.loss,
+        amount,
+        self.growth_delegation,
+        "snapshot.growth_delegation",
+      )?,
+    };
+    Ok((growth, loss))
   }
 }
@@ -1,23 +1,47 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Order, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
 use cw_lib::models::{Owner, Token};
 
-use crate::models::{ClientAccount, Snapshot};
+use crate::models::{
+  ClientAccount, ClientLimits, ClientMetadata, ClientStatus, Config, DelegationAccount,
+  DelegationType, FeeConfig, Operation, PoolIndices, ShareTokenConfig, Snapshot,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
   pub owner: Owner,
   pub token: Token,
+  pub fee: Option<FeeConfig>,
+  pub config: Option<Config>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
   SetClient {
     address: Addr,
+    metadata: Option<ClientMetadata>,
+  },
+  UpdateClientMetadata {
+    address: Addr,
+    metadata: ClientMetadata,
+  },
+  SetClientLimits {
+    address: Addr,
+    limits: Option<ClientLimits>,
+  },
+  SetClientStatus {
+    address: Addr,
+    status: ClientStatus,
+  },
+  RemoveClient {
+    address: Addr,
   },
   Delegate {
     growth: Uint128,
     profit: Uint128,
+    mint_shares: Option<bool>,
+    beneficiary: Option<Addr>,
   },
   ReceivePayment {
     sender: Option<Addr>,
@@ -27,8 +51,76 @@ pub enum ExecuteMsg {
     recipient: Addr,
     amount: Uint128,
   },
+  Reserve {
+    amount: Uint128,
+    id: String,
+  },
+  Release {
+    id: String,
+  },
+  Settle {
+    id: String,
+    payout: Uint128,
+    recipient: Addr,
+  },
   SendProfit {},
-  Withdraw {},
+  Compound {
+    pool: DelegationType,
+    amount: Option<Uint128>,
+  },
+  TransferAccount {
+    recipient: Addr,
+  },
+  Rebalance {
+    growth_to_profit: Option<Uint128>,
+    profit_to_growth: Option<Uint128>,
+  },
+  Withdraw {
+    growth: Option<Uint128>,
+    profit: Option<Uint128>,
+  },
+  ClaimUnbonded {},
+  ClaimFees {},
+  SetFeeConfig {
+    fee: FeeConfig,
+  },
+  SetUnbondingPeriod {
+    seconds: u64,
+  },
+  UpdateConfig {
+    config: Config,
+  },
+  Amortize {
+    limit: Option<u32>,
+  },
+  SettleAccount {
+    max_snapshots: u32,
+  },
+  CreateShareToken {
+    config: ShareTokenConfig,
+  },
+  Pause {
+    operations: Vec<Operation>,
+  },
+  Unpause {
+    operations: Vec<Operation>,
+  },
+  Receive(Cw20ReceiveMsg),
+}
+
+/// Messages accepted through the CW20 Receive hook.
+#[cw_serde]
+pub enum ReceiveMsg {
+  Delegate {
+    growth: Uint128,
+    profit: Uint128,
+    mint_shares: Option<bool>,
+    beneficiary: Option<Addr>,
+  },
+  Redeem {},
+  ReceivePayment {
+    sender: Option<Addr>,
+  },
 }
 
 #[cw_serde]
@@ -36,26 +128,86 @@ pub enum QueryMsg {
   Client {
     address: Addr,
   },
+  Config {},
   Select {
     fields: Option<Vec<String>>,
     wallet: Option<Addr>,
   },
+  Invariants {
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    totals: Option<AccountTotals>,
+  },
+  /// Deprecated: no snapshots are taken under index accounting, so this only
+  /// pages through the legacy snapshots not yet released by every account.
+  Snapshots {
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    order: Option<Order>,
+  },
+  Delegators {
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+  },
+  Clients {
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+  },
+  /// Legacy Delegation records of an account not yet converted, along with
+  /// the account's index data, which is all a converted account has.
+  Delegations {
+    wallet: Addr,
+    pool: DelegationType,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+  },
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+  pub fee: Option<FeeConfig>,
+  pub unbonding_period: Option<u64>,
+  pub config: Option<Config>,
+}
 
 #[cw_serde]
 pub struct PoolsView {
   pub growth: Uint128,
   pub profit: Uint128,
+  pub indices: PoolIndices,
 }
 
 #[cw_serde]
 pub struct StatsView {
   pub n_delegation_accounts: u32,
   pub n_client_accounts: u32,
+  /// Deprecated: number of legacy snapshots not yet released
   pub n_snapshots: u32,
+  pub lifetime_fees: Uint128,
+  pub fees_claimable: Uint128,
+  pub amortization_batch_size: u32,
+  pub memoization_queue_len: u32,
+  pub n_amortized: u64,
+}
+
+#[cw_serde]
+pub struct SharesView {
+  pub token: Addr,
+  pub supply: Uint128,
+  pub vault_value: Uint128,
+}
+
+#[cw_serde]
+pub struct StatusView {
+  pub paused_operations: Vec<Operation>,
+}
+
+#[cw_serde]
+pub struct UnbondingView {
+  pub id: u64,
+  pub amount: Uint128,
+  pub created_at: Timestamp,
+  pub matures_at: Timestamp,
 }
 
 #[cw_serde]
@@ -67,19 +219,119 @@ pub struct AccountView {
   pub growth_claimable: Uint128,
   pub liquidity_spent: Uint128,
   pub revenue_generated: Uint128,
+  pub unbondings: Vec<UnbondingView>,
 }
 
 #[cw_serde]
 pub struct SelectResponse {
   pub total_liquidity: Option<Uint128>,
+  pub reserved_liquidity: Option<Uint128>,
+  pub free_liquidity: Option<Uint128>,
   pub total_profit_claimable: Option<Uint128>,
+  /// Deprecated: latest legacy snapshots not yet released
   pub snapshots: Option<Vec<Snapshot>>,
   pub pools: Option<PoolsView>,
   pub account: Option<AccountView>,
   pub stats: Option<StatsView>,
+  pub status: Option<StatusView>,
+  pub shares: Option<SharesView>,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+  pub config: Config,
 }
 
 #[cw_serde]
 pub struct ClientResponse {
   pub client: ClientAccount,
 }
+
+#[cw_serde]
+#[derive(Default)]
+pub struct AccountTotals {
+  pub n_accounts: u32,
+  pub growth_delegation: Uint128,
+  pub profit_delegation: Uint128,
+  pub growth: Uint128,
+  pub loss: Uint128,
+  pub profit: Uint128,
+  pub liquidity: Uint128,
+}
+
+#[cw_serde]
+pub struct InvariantViolation {
+  pub name: String,
+  pub expected: Uint128,
+  pub actual: Uint128,
+}
+
+#[cw_serde]
+pub struct InvariantsReport {
+  pub net_liquidity: Uint128,
+  pub net_profit: Uint128,
+  pub net_fees: Uint128,
+  pub net_growth_delegation: Uint128,
+  pub net_profit_delegation: Uint128,
+  pub unbonding_amount: Uint128,
+  pub balance: Uint128,
+  pub expected_balance: Uint128,
+  pub liquidity_dust: Uint128,
+  pub profit_dust: Uint128,
+  pub balance_surplus: Uint128,
+  pub violations: Vec<InvariantViolation>,
+}
+
+#[cw_serde]
+pub struct InvariantsResponse {
+  pub totals: AccountTotals,
+  pub next: Option<Addr>,
+  pub report: Option<InvariantsReport>,
+}
+
+#[cw_serde]
+pub struct SnapshotEntry {
+  pub index: Uint128,
+  pub snapshot: Snapshot,
+}
+
+#[cw_serde]
+pub struct SnapshotsResponse {
+  pub snapshots: Vec<SnapshotEntry>,
+}
+
+#[cw_serde]
+pub struct DelegatorView {
+  pub account: DelegationAccount,
+  pub growth_delegation: Uint128,
+  pub profit_delegation: Uint128,
+  pub growth_claimable: Uint128,
+  pub loss_claimable: Uint128,
+  pub profit_claimable: Uint128,
+}
+
+#[cw_serde]
+pub struct DelegatorsResponse {
+  pub delegators: Vec<DelegatorView>,
+}
+
+#[cw_serde]
+pub struct ClientsResponse {
+  pub clients: Vec<ClientAccount>,
+}
+
+#[cw_serde]
+pub struct DelegationView {
+  pub seq_no: Uint128,
+  pub amount: Uint128,
+  pub i_snapshot: Uint128,
+  pub growth: Uint128,
+  pub loss: Uint128,
+  pub profit: Uint128,
+}
+
+#[cw_serde]
+pub struct DelegationsResponse {
+  pub account: Option<DelegationAccount>,
+  pub delegations: Vec<DelegationView>,
+}
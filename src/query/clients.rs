@@ -0,0 +1,25 @@
+use crate::{
+  models::ContractResult, msg::ClientsResponse, state::CLIENT_ACCOUNTS, util::get_page_limit,
+};
+use cosmwasm_std::{Addr, Deps, Order};
+use cw_storage_plus::Bound;
+
+/// Page through ClientAccounts in address order.
+pub fn clients(
+  deps: Deps,
+  start_after: Option<Addr>,
+  limit: Option<u32>,
+) -> ContractResult<ClientsResponse> {
+  let clients = CLIENT_ACCOUNTS
+    .range(
+      deps.storage,
+      start_after.map(Bound::exclusive),
+      None,
+      Order::Ascending,
+    )
+    .take(get_page_limit(limit))
+    .map(|result| result.map(|(_, client)| client))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(ClientsResponse { clients })
+}
@@ -0,0 +1,80 @@
+use crate::{
+  models::{ContractResult, DelegationType},
+  msg::{DelegationView, DelegationsResponse},
+  state::{DELEGATION_ACCOUNTS, GROWTH_DELEGATIONS, PROFIT_DELEGATIONS},
+  util::{get_page_limit, validate_addr},
+};
+use cosmwasm_std::{Addr, Deps, Order, Uint128};
+use cw_storage_plus::Bound;
+
+/// Page through a wallet's legacy Delegation records in the given pool, along
+/// with what each one has accrued between its starting snapshot and the next
+/// record's (or the last snapshot, for the most recent record). Records are
+/// removed once the account settles under PoolIndices, so the account itself
+/// is returned too, with its converted delegation, indices and memoized claims.
+pub fn delegations(
+  deps: Deps,
+  wallet: Addr,
+  pool: DelegationType,
+  start_after: Option<Uint128>,
+  limit: Option<u32>,
+) -> ContractResult<DelegationsResponse> {
+  validate_addr(deps.api, &wallet)?;
+
+  let account = match DELEGATION_ACCOUNTS.may_load(deps.storage, wallet.clone())? {
+    Some(account) => account,
+    None => {
+      return Ok(DelegationsResponse {
+        account: None,
+        delegations: vec![],
+      })
+    },
+  };
+
+  let delegations_map = match pool {
+    DelegationType::Growth => &GROWTH_DELEGATIONS,
+    DelegationType::Profit => &PROFIT_DELEGATIONS,
+  };
+
+  let limit = get_page_limit(limit);
+
+  // load one extra record so that the last one in the page knows where its
+  // accrual ends.
+  let records = delegations_map
+    .prefix(wallet)
+    .range(
+      deps.storage,
+      start_after.map(|i| Bound::exclusive(i.u128())),
+      None,
+      Order::Ascending,
+    )
+    .take(limit + 1)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let mut delegations: Vec<DelegationView> = Vec::with_capacity(limit);
+
+  for (i, (seq_no, d0)) in records.iter().enumerate().take(limit) {
+    let maybe_d1 = records.get(i + 1).map(|(_, d1)| d1);
+    let (growth, loss) =
+      account.process_delegation_readonly(deps.storage, pool.clone(), d0, maybe_d1)?;
+
+    let (growth, profit) = match pool {
+      DelegationType::Growth => (growth, Uint128::zero()),
+      DelegationType::Profit => (Uint128::zero(), growth),
+    };
+
+    delegations.push(DelegationView {
+      seq_no: (*seq_no).into(),
+      amount: d0.amount,
+      i_snapshot: d0.i_snapshot,
+      growth,
+      loss,
+      profit,
+    });
+  }
+
+  Ok(DelegationsResponse {
+    account: Some(account),
+    delegations,
+  })
+}
@@ -0,0 +1,48 @@
+use crate::{
+  models::ContractResult,
+  msg::{DelegatorView, DelegatorsResponse},
+  state::DELEGATION_ACCOUNTS,
+  util::get_page_limit,
+};
+use cosmwasm_std::{Addr, Deps, Env, Order};
+use cw_storage_plus::Bound;
+
+/// Page through DelegationAccounts along with what each has delegated and
+/// what is currently claimable. The share token vault isn't listed.
+pub fn delegators(
+  deps: Deps,
+  env: Env,
+  start_after: Option<Addr>,
+  limit: Option<u32>,
+) -> ContractResult<DelegatorsResponse> {
+  let delegators = DELEGATION_ACCOUNTS
+    .range(
+      deps.storage,
+      start_after.map(Bound::exclusive),
+      None,
+      Order::Ascending,
+    )
+    .filter(|result| {
+      result
+        .as_ref()
+        .map_or(true, |(owner, _)| *owner != env.contract.address)
+    })
+    .take(get_page_limit(limit))
+    .map(|result| {
+      let (_, account) = result?;
+      let (growth_delegation, profit_delegation) = account.get_delegation_amounts(deps.storage)?;
+      let (growth_claimable, loss_claimable, profit_claimable) =
+        account.get_claimable_amounts(deps.storage)?;
+      Ok(DelegatorView {
+        account,
+        growth_delegation,
+        profit_delegation,
+        growth_claimable,
+        loss_claimable,
+        profit_claimable,
+      })
+    })
+    .collect::<ContractResult<Vec<_>>>()?;
+
+  Ok(DelegatorsResponse { delegators })
+}
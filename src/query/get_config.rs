@@ -0,0 +1,8 @@
+use crate::{models::ContractResult, msg::ConfigResponse, state::CONFIG};
+use cosmwasm_std::Deps;
+
+pub fn get_config(deps: Deps) -> ContractResult<ConfigResponse> {
+  Ok(ConfigResponse {
+    config: CONFIG.load(deps.storage)?,
+  })
+}
@@ -0,0 +1,187 @@
+use crate::{
+  models::ContractResult,
+  msg::{AccountTotals, InvariantViolation, InvariantsReport, InvariantsResponse},
+  state::{
+    DELEGATION_ACCOUNTS, NET_FEES, NET_GROWTH_DELEGATION, NET_LIQUIDITY, NET_PROFIT,
+    NET_PROFIT_DELEGATION, TOKEN, UNBONDING_POOL,
+  },
+  util::{add, get_page_limit},
+};
+use cosmwasm_std::{Addr, Deps, Env, Order, Uint128};
+use cw20::{BalanceResponse, Cw20QueryMsg};
+use cw_lib::models::Token;
+use cw_storage_plus::Bound;
+
+/// Sum what each DelegationAccount is owed, one page at a time, and compare
+/// the sums with the contract-level accumulators once the last page is
+/// reached. Totals from the previous page are passed back in by the caller.
+pub fn invariants(
+  deps: Deps,
+  env: Env,
+  start_after: Option<Addr>,
+  limit: Option<u32>,
+  maybe_totals: Option<AccountTotals>,
+) -> ContractResult<InvariantsResponse> {
+  let limit = get_page_limit(limit);
+  let mut totals = maybe_totals.unwrap_or_default();
+
+  let accounts = DELEGATION_ACCOUNTS
+    .range(
+      deps.storage,
+      start_after.map(Bound::exclusive),
+      None,
+      Order::Ascending,
+    )
+    .take(limit + 1)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let is_last_page = accounts.len() <= limit;
+  let mut next: Option<Addr> = None;
+
+  for (owner, account) in accounts.iter().take(limit) {
+    let (growth_delegation, profit_delegation) = account.get_delegation_amounts(deps.storage)?;
+    let (growth, loss, profit) = account.get_claimable_amounts(deps.storage)?;
+
+    let delegation = add(growth_delegation, profit_delegation, "account.delegation")?;
+    let liquidity = add(delegation, growth, "account.liquidity")?.saturating_sub(loss);
+
+    totals.n_accounts = add(totals.n_accounts, 1, "totals.n_accounts")?;
+    totals.growth_delegation = add(
+      totals.growth_delegation,
+      growth_delegation,
+      "totals.growth_delegation",
+    )?;
+    totals.profit_delegation = add(
+      totals.profit_delegation,
+      profit_delegation,
+      "totals.profit_delegation",
+    )?;
+    totals.growth = add(totals.growth, growth, "totals.growth")?;
+    totals.loss = add(totals.loss, loss, "totals.loss")?;
+    totals.profit = add(totals.profit, profit, "totals.profit")?;
+    totals.liquidity = add(totals.liquidity, liquidity, "totals.liquidity")?;
+
+    next = Some(owner.clone());
+  }
+
+  let report = if is_last_page {
+    next = None;
+    Some(build_report(deps, &env, &totals)?)
+  } else {
+    None
+  };
+
+  Ok(InvariantsResponse {
+    totals,
+    next,
+    report,
+  })
+}
+
+fn build_report(
+  deps: Deps,
+  env: &Env,
+  totals: &AccountTotals,
+) -> ContractResult<InvariantsReport> {
+  let net_liquidity = NET_LIQUIDITY.load(deps.storage)?;
+  let net_profit = NET_PROFIT.load(deps.storage)?;
+  let net_fees = NET_FEES.load(deps.storage)?;
+  let net_growth_delegation = NET_GROWTH_DELEGATION.load(deps.storage)?;
+  let net_profit_delegation = NET_PROFIT_DELEGATION.load(deps.storage)?;
+  let unbonding_amount = UNBONDING_POOL.load(deps.storage)?.amount;
+
+  // liquidity held for unbondings is no longer owed to any account
+  let delegated_liquidity = net_liquidity.saturating_sub(unbonding_amount);
+  let expected_balance = add(
+    add(net_liquidity, net_profit, "expected_balance")?,
+    net_fees,
+    "expected_balance",
+  )?;
+  let balance = query_balance(deps, env)?;
+
+  let mut violations: Vec<InvariantViolation> = vec![];
+
+  // delegation amounts are never rounded, so they must match exactly
+  for (name, expected, actual) in [
+    (
+      "growth_delegation",
+      net_growth_delegation,
+      totals.growth_delegation,
+    ),
+    (
+      "profit_delegation",
+      net_profit_delegation,
+      totals.profit_delegation,
+    ),
+  ] {
+    if expected != actual {
+      violations.push(violation(name, expected, actual));
+    }
+  }
+
+  // claims round in the house's favor: growth and profit down and losses up.
+  // Accounts may be owed less than the house holds, which is reported as
+  // dust, but being owed more means the accounting has gone wrong.
+  for (name, expected, actual) in [
+    ("liquidity", delegated_liquidity, totals.liquidity),
+    ("profit", net_profit, totals.profit),
+  ] {
+    if actual > expected {
+      violations.push(violation(name, expected, actual));
+    }
+  }
+
+  if balance < expected_balance {
+    violations.push(violation("balance", expected_balance, balance));
+  }
+
+  Ok(InvariantsReport {
+    net_liquidity,
+    net_profit,
+    net_fees,
+    net_growth_delegation,
+    net_profit_delegation,
+    unbonding_amount,
+    balance,
+    expected_balance,
+    liquidity_dust: delegated_liquidity.saturating_sub(totals.liquidity),
+    profit_dust: net_profit.saturating_sub(totals.profit),
+    balance_surplus: balance.saturating_sub(expected_balance),
+    violations,
+  })
+}
+
+fn violation(
+  name: &str,
+  expected: Uint128,
+  actual: Uint128,
+) -> InvariantViolation {
+  InvariantViolation {
+    name: name.to_owned(),
+    expected,
+    actual,
+  }
+}
+
+fn query_balance(
+  deps: Deps,
+  env: &Env,
+) -> ContractResult<Uint128> {
+  Ok(match TOKEN.load(deps.storage)? {
+    Token::Native { denom } => {
+      deps
+        .querier
+        .query_balance(env.contract.address.clone(), denom)?
+        .amount
+    },
+    Token::Cw20 { address } => {
+      let resp: BalanceResponse = deps.querier.query_wasm_smart(
+        address,
+        &Cw20QueryMsg::Balance {
+          address: env.contract.address.to_string(),
+        },
+      )?;
+      resp.balance
+    },
+  })
+}
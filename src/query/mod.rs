@@ -1,5 +1,17 @@
+mod clients;
+mod delegations;
+mod delegators;
 mod get_client;
+mod get_config;
+mod invariants;
 mod select;
+mod snapshots;
 
+pub use clients::clients;
+pub use delegations::delegations;
+pub use delegators::delegators;
 pub use get_client::get_client;
+pub use get_config::get_config;
+pub use invariants::invariants;
 pub use select::select;
+pub use snapshots::snapshots;
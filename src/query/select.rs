@@ -1,17 +1,21 @@
 use crate::{
   models::{ContractResult, DelegationType},
-  msg::{AccountView, PoolsView, SelectResponse, StatsView},
+  msg::{AccountView, PoolsView, SelectResponse, SharesView, StatsView, StatusView, UnbondingView},
   state::{
-    CLIENT_ACCOUNTS, CLIENT_ACCOUNTS_LEN, DELEGATION_ACCOUNTS, DELEGATION_ACCOUNTS_LEN,
-    NET_GROWTH_DELEGATION, NET_LIQUIDITY, NET_PROFIT, NET_PROFIT_DELEGATION, SNAPSHOTS,
-    SNAPSHOTS_LEN,
+    get_free_liquidity, CLIENT_ACCOUNTS, CLIENT_ACCOUNTS_LEN, CONFIG, DELEGATION_ACCOUNTS,
+    DELEGATION_ACCOUNTS_LEN, LIFETIME_FEES, MEMOIZATION_QUEUE_LEN, NET_FEES, NET_GROWTH_DELEGATION,
+    NET_LIQUIDITY, NET_LIQUIDITY_RESERVED, NET_PROFIT, NET_PROFIT_DELEGATION, N_AMORTIZED,
+    PAUSED_OPERATIONS, POOL_INDICES, SHARE_SUPPLY, SHARE_TOKEN, SNAPSHOTS, SNAPSHOTS_LEN,
+    UNBONDINGS, UNBONDING_POOL,
   },
+  util::add,
 };
-use cosmwasm_std::{Addr, Deps, Order, Uint128};
+use cosmwasm_std::{Addr, Deps, Env, Order, Uint128};
 use cw_repository::client::Repository;
 
 pub fn select(
   deps: Deps,
+  env: Env,
   maybe_fields: Option<Vec<String>>,
   maybe_wallet: Option<Addr>,
 ) -> ContractResult<SelectResponse> {
@@ -20,25 +24,36 @@ pub fn select(
     // total spendable liquidity available
     total_liquidity: loader.get("liquidity", &NET_LIQUIDITY)?,
 
+    // total liquidity reserved by clients for in-flight payments
+    reserved_liquidity: loader.get("reserved_liquidity", &NET_LIQUIDITY_RESERVED)?,
+
+    // liquidity available for new payments, reservations and withdrawals
+    free_liquidity: loader.view("free_liquidity", || {
+      Ok(Some(get_free_liquidity(deps.storage)?))
+    })?,
+
     // total unclaimed profit stored on behalf of all delegators
     total_profit_claimable: loader.get("profit", &NET_PROFIT)?,
 
-    // 20 most recent Snapshots in time-desc order
+    // most recent legacy Snapshots in time-desc order, up to the configured limit
     snapshots: loader.view("snapshots", || {
+      let limit = CONFIG.load(deps.storage)?.snapshot_query_limit as usize;
       Ok(Some(
         SNAPSHOTS
           .range(deps.storage, None, None, Order::Descending)
           .map(|result| result.unwrap().1)
-          .take(20)
+          .take(limit)
           .collect(),
       ))
     })?,
 
-    // total delegation amounts for both the revenue growth and profit pools
+    // total delegation amounts and per-share indices for both the revenue
+    // growth and profit pools
     pools: loader.view("pools", || {
       Ok(Some(PoolsView {
         growth: NET_GROWTH_DELEGATION.load(deps.storage)?,
         profit: NET_PROFIT_DELEGATION.load(deps.storage)?,
+        indices: POOL_INDICES.load(deps.storage)?,
       }))
     })?,
 
@@ -48,38 +63,66 @@ pub fn select(
         n_delegation_accounts: DELEGATION_ACCOUNTS_LEN.load(deps.storage)?,
         n_client_accounts: CLIENT_ACCOUNTS_LEN.load(deps.storage)?,
         n_snapshots: SNAPSHOTS_LEN.load(deps.storage)?,
+        lifetime_fees: LIFETIME_FEES.load(deps.storage)?,
+        fees_claimable: NET_FEES.load(deps.storage)?,
+        amortization_batch_size: CONFIG.load(deps.storage)?.amortization_batch_size,
+        memoization_queue_len: MEMOIZATION_QUEUE_LEN.load(deps.storage)?,
+        n_amortized: N_AMORTIZED.load(deps.storage)?,
+      }))
+    })?,
+
+    // share token supply and the value of the growth pool vault behind it
+    shares: loader.view("shares", || {
+      Ok(match SHARE_TOKEN.may_load(deps.storage)? {
+        Some(token) => {
+          let vault_value =
+            match DELEGATION_ACCOUNTS.may_load(deps.storage, env.contract.address)? {
+              Some(vault) => vault.get_growth_value(deps.storage)?,
+              None => Uint128::zero(),
+            };
+          Some(SharesView {
+            token,
+            supply: SHARE_SUPPLY.load(deps.storage)?,
+            vault_value,
+          })
+        },
+        None => None,
+      })
+    })?,
+
+    // operations currently paused by the house
+    status: loader.view("status", || {
+      Ok(Some(StatusView {
+        paused_operations: PAUSED_OPERATIONS.load(deps.storage)?,
       }))
     })?,
 
     // data associated with the given "wallet" address argument
     account: loader.view_by_wallet("account", maybe_wallet, |wallet| {
-      let (mut growth, mut loss, mut profit) =
-        match DELEGATION_ACCOUNTS.may_load(deps.storage, wallet.clone())? {
-          Some(account) => {
-            let (growth, loss) = account
-              .claim_readonly(deps.storage, DelegationType::Growth)
-              .unwrap_or((Uint128::zero(), Uint128::zero()));
-
-            let profit = account
-              .claim_readonly(deps.storage, DelegationType::Profit)
-              .unwrap_or((Uint128::zero(), Uint128::zero()))
-              .0;
-
-            (growth, loss, profit)
-          },
-          None => (Uint128::zero(), Uint128::zero(), Uint128::zero()),
-        };
-
+      let mut growth_claimable = Uint128::zero();
+      let mut loss_claimable = Uint128::zero();
+      let mut profit_claimable = Uint128::zero();
       let mut growth_delegation = Uint128::zero();
       let mut profit_delegation = Uint128::zero();
 
       if let Some(account) = DELEGATION_ACCOUNTS.may_load(deps.storage, wallet.clone())? {
-        growth += account.memoized_growth;
-        loss += account.memoized_loss;
-        profit += account.memoized_profit;
+        let (growth, loss) = account
+          .claim_readonly(deps.storage, DelegationType::Growth)
+          .unwrap_or((Uint128::zero(), Uint128::zero()));
+
+        let profit = account
+          .claim_readonly(deps.storage, DelegationType::Profit)
+          .unwrap_or((Uint128::zero(), Uint128::zero()))
+          .0;
+
+        // what's claimable includes whatever was memoized before
+        growth_claimable = add(growth, account.memoized_growth, "account.growth")?;
+        loss_claimable = add(loss, account.memoized_loss, "account.loss")?;
+        profit_claimable = add(profit, account.memoized_profit, "account.profit")?;
+
         if let Ok((growth_deleg, profit_deleg)) = account.get_delegation_amounts(deps.storage) {
-          growth_delegation += growth_deleg;
-          profit_delegation += profit_deleg;
+          growth_delegation = growth_deleg;
+          profit_delegation = profit_deleg;
         }
       }
 
@@ -89,14 +132,30 @@ pub fn select(
           None => (Uint128::zero(), Uint128::zero()),
         };
 
+      let unbonding_pool = UNBONDING_POOL.load(deps.storage)?;
+      let unbondings = UNBONDINGS
+        .prefix(wallet.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|result| {
+          let (id, unbonding) = result.unwrap();
+          UnbondingView {
+            id,
+            amount: unbonding_pool.get_amount(&unbonding),
+            created_at: unbonding.created_at,
+            matures_at: unbonding.matures_at,
+          }
+        })
+        .collect();
+
       Ok(Some(AccountView {
         growth_delegation,
         profit_delegation,
         liquidity_spent,
         revenue_generated,
-        growth_claimable: growth,
-        profit_claimable: profit,
-        loss_claimable: loss,
+        growth_claimable,
+        profit_claimable,
+        loss_claimable,
+        unbondings,
       }))
     })?,
   })
@@ -0,0 +1,40 @@
+use crate::{
+  models::ContractResult,
+  msg::{SnapshotEntry, SnapshotsResponse},
+  state::{CONFIG, SNAPSHOTS},
+};
+use cosmwasm_std::{Deps, Order, Uint128};
+use cw_storage_plus::Bound;
+
+/// Page through the legacy Snapshots not yet released by every Delegation
+/// record spanning them, in either order.
+pub fn snapshots(
+  deps: Deps,
+  start_after: Option<Uint128>,
+  limit: Option<u32>,
+  maybe_order: Option<Order>,
+) -> ContractResult<SnapshotsResponse> {
+  let max_limit = CONFIG.load(deps.storage)?.snapshot_query_limit;
+  let limit = limit.unwrap_or(max_limit).clamp(1, max_limit) as usize;
+  let order = maybe_order.unwrap_or(Order::Descending);
+  let bound = start_after.map(|i| Bound::exclusive(i.u128()));
+
+  let (min, max) = match order {
+    Order::Ascending => (bound, None),
+    Order::Descending => (None, bound),
+  };
+
+  let snapshots = SNAPSHOTS
+    .range(deps.storage, min, max, order)
+    .take(limit)
+    .map(|result| {
+      let (index, snapshot) = result?;
+      Ok(SnapshotEntry {
+        index: index.into(),
+        snapshot,
+      })
+    })
+    .collect::<ContractResult<Vec<_>>>()?;
+
+  Ok(SnapshotsResponse { snapshots })
+}
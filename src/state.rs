@@ -1,30 +1,42 @@
 use std::collections::HashSet;
 
-use crate::models::Snapshot;
-use crate::models::{ClientAccount, ContractResult, Delegation, DelegationAccount};
+use crate::error::ContractError;
+use crate::models::{ClientAccount, ClientMetadata, Config, ContractResult};
+use crate::models::{Delegation, DelegationAccount, FeeConfig, Operation, PoolIndices};
+use crate::models::{Reservation, Snapshot, Unbonding, UnbondingPool};
 use crate::msg::InstantiateMsg;
-use crate::util::validate_addr;
-use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Storage, Uint128};
+use crate::util::{add, decrement, increment, validate_addr};
+use cosmwasm_std::{Addr, Api, Deps, DepsMut, Env, MessageInfo, Order, Storage, Uint128};
 use cw_acl::client::Acl;
 use cw_lib::models::{Owner, Token};
-use cw_storage_plus::{Deque, Item, Map};
+use cw_storage_plus::{Item, Map};
 
 pub const OWNER: Item<Owner> = Item::new("owner");
 pub const TOKEN: Item<Token> = Item::new("token");
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const PAUSED_OPERATIONS: Item<Vec<Operation>> = Item::new("paused_operations");
+
+pub const FEE_CONFIG: Item<FeeConfig> = Item::new("fee_config");
+pub const NET_FEES: Item<Uint128> = Item::new("net_fees");
+pub const LIFETIME_FEES: Item<Uint128> = Item::new("lifetime_fees");
 
 pub const NET_GROWTH_DELEGATION: Item<Uint128> = Item::new("net_growth_delegation");
 pub const NET_PROFIT_DELEGATION: Item<Uint128> = Item::new("net_profit_delegation");
 pub const NET_LIQUIDITY: Item<Uint128> = Item::new("net_liquidity");
 pub const NET_PROFIT: Item<Uint128> = Item::new("net_profit");
-pub const NET_PCT_LIQUIDITY_ALLOCATED: Item<u32> = Item::new("net_pct_liquidity_allocated");
+/// Liquidity reserved by clients, kept in the formerly unused
+/// NET_PCT_LIQUIDITY_ALLOCATED item
+pub const NET_LIQUIDITY_RESERVED: Item<Uint128> = Item::new("net_pct_liquidity_allocated");
 
 pub const GROWTH_DELEGATOR_COUNT: Item<u32> = Item::new("growth_delegator_count");
 pub const PROFIT_DELEGATOR_COUNT: Item<u32> = Item::new("profit_delegator_count");
 
+pub const POOL_INDICES: Item<PoolIndices> = Item::new("pool_indices");
+
 pub const SNAPSHOTS: Map<u128, Snapshot> = Map::new("snapshots");
 pub const SNAPSHOTS_LEN: Item<u32> = Item::new("snapshot_len");
 pub const SNAPSHOTS_INDEX: Item<Uint128> = Item::new("snapshot_index");
-pub const SNAPSHOT_SEQ_NO: Item<Uint128> = Item::new("snapshot_seq_no");
 
 pub const GROWTH_DELEGATIONS: Map<(Addr, u128), Delegation> = Map::new("growth_delegations");
 pub const GROWTH_DELEGATIONS_SEQ_NO: Map<Addr, u128> = Map::new("growth_delegations_seq_no");
@@ -38,7 +50,22 @@ pub const DELEGATION_ACCOUNTS_LEN: Item<u32> = Item::new("delegation_accounts_le
 pub const CLIENT_ACCOUNTS: Map<Addr, ClientAccount> = Map::new("client_accounts");
 pub const CLIENT_ACCOUNTS_LEN: Item<u32> = Item::new("client_accounts_len");
 
-pub const MEMOIZATION_QUEUE: Deque<Addr> = Deque::new("memoization_queue");
+pub const RESERVATIONS: Map<(Addr, String), Reservation> = Map::new("reservations");
+
+pub const UNBONDING_PERIOD: Item<u64> = Item::new("unbonding_period");
+pub const UNBONDING_POOL: Item<UnbondingPool> = Item::new("unbonding_pool");
+pub const UNBONDINGS: Map<(Addr, u64), Unbonding> = Map::new("unbondings");
+pub const UNBONDINGS_SEQ_NO: Item<u64> = Item::new("unbondings_seq_no");
+
+pub const SHARE_TOKEN: Item<Addr> = Item::new("share_token");
+pub const SHARE_SUPPLY: Item<Uint128> = Item::new("share_supply");
+
+pub const N_AMORTIZED: Item<u64> = Item::new("n_amortized");
+
+pub const MEMOIZATION_QUEUE: Map<u64, Addr> = Map::new("memoization_queue_entries");
+pub const MEMOIZATION_QUEUE_POSITIONS: Map<Addr, u64> = Map::new("memoization_queue_positions");
+pub const MEMOIZATION_QUEUE_TAIL: Item<u64> = Item::new("memoization_queue_tail");
+pub const MEMOIZATION_QUEUE_LEN: Item<u32> = Item::new("memoization_queue_len");
 
 /// Initialize contract state data.
 pub fn initialize(
@@ -55,21 +82,126 @@ pub fn initialize(
     },
   )?;
 
+  let fee_config = msg.fee.clone().unwrap_or_default();
+  validate_fee_config(deps.api, &fee_config)?;
+
+  let config = msg.config.clone().unwrap_or_default();
+  validate_config(&config)?;
+
   OWNER.save(deps.storage, &msg.owner)?;
   TOKEN.save(deps.storage, &msg.token)?;
+  CONFIG.save(deps.storage, &config)?;
+  PAUSED_OPERATIONS.save(deps.storage, &vec![])?;
+  FEE_CONFIG.save(deps.storage, &fee_config)?;
+  NET_FEES.save(deps.storage, &Uint128::zero())?;
+  LIFETIME_FEES.save(deps.storage, &Uint128::zero())?;
   NET_GROWTH_DELEGATION.save(deps.storage, &Uint128::zero())?;
   NET_PROFIT_DELEGATION.save(deps.storage, &Uint128::zero())?;
   NET_LIQUIDITY.save(deps.storage, &Uint128::zero())?;
-  NET_PCT_LIQUIDITY_ALLOCATED.save(deps.storage, &0)?;
+  NET_LIQUIDITY_RESERVED.save(deps.storage, &Uint128::zero())?;
   NET_PROFIT.save(deps.storage, &Uint128::zero())?;
   DELEGATION_ACCOUNTS_LEN.save(deps.storage, &0)?;
   SNAPSHOTS_LEN.save(deps.storage, &0)?;
   SNAPSHOTS_INDEX.save(deps.storage, &Uint128::zero())?;
-  SNAPSHOT_SEQ_NO.save(deps.storage, &Uint128::zero())?;
+  POOL_INDICES.save(deps.storage, &PoolIndices::default())?;
   GROWTH_DELEGATOR_COUNT.save(deps.storage, &0)?;
   PROFIT_DELEGATOR_COUNT.save(deps.storage, &0)?;
   CLIENT_ACCOUNTS_LEN.save(deps.storage, &0)?;
+  UNBONDING_PERIOD.save(deps.storage, &0)?;
+  UNBONDING_POOL.save(deps.storage, &UnbondingPool::default())?;
+  UNBONDINGS_SEQ_NO.save(deps.storage, &0)?;
+  N_AMORTIZED.save(deps.storage, &0)?;
+  MEMOIZATION_QUEUE_TAIL.save(deps.storage, &0)?;
+  MEMOIZATION_QUEUE_LEN.save(deps.storage, &0)?;
+  SHARE_SUPPLY.save(deps.storage, &Uint128::zero())?;
+
+  Ok(())
+}
+
+/// Validate a FeeConfig's rate and recipient address.
+pub fn validate_fee_config(
+  api: &dyn Api,
+  fee_config: &FeeConfig,
+) -> ContractResult<()> {
+  if fee_config.rate > 10_000 {
+    return Err(ContractError::InvalidFee {});
+  }
+  if let Some(recipient) = &fee_config.recipient {
+    validate_addr(api, recipient)?;
+  }
+  Ok(())
+}
 
+/// Validate the Config's limits.
+pub fn validate_config(config: &Config) -> ContractResult<()> {
+  if config.snapshot_query_limit == 0 || config.amortization_snapshot_limit == 0 {
+    return Err(ContractError::InvalidConfig {});
+  }
+  if let Some(max_account_delegation) = config.max_account_delegation {
+    if max_account_delegation < config.min_delegation {
+      return Err(ContractError::InvalidConfig {});
+    }
+  }
+  Ok(())
+}
+
+/// Max length of each ClientMetadata field other than the description
+const MAX_CLIENT_METADATA_LEN: usize = 256;
+/// Max length of a ClientMetadata description
+const MAX_CLIENT_DESCRIPTION_LEN: usize = 1024;
+
+/// Return an error if any of the ClientMetadata's fields is too long to store.
+pub fn validate_client_metadata(metadata: &ClientMetadata) -> ContractResult<()> {
+  let fields = [
+    (&metadata.name, MAX_CLIENT_METADATA_LEN),
+    (&metadata.description, MAX_CLIENT_DESCRIPTION_LEN),
+    (&metadata.url, MAX_CLIENT_METADATA_LEN),
+    (&metadata.category, MAX_CLIENT_METADATA_LEN),
+    (&metadata.logo, MAX_CLIENT_METADATA_LEN),
+  ];
+  for (field, max_len) in fields {
+    if field.as_ref().map_or(false, |value| value.len() > max_len) {
+      return Err(ContractError::InvalidClientMetadata {});
+    }
+  }
+  Ok(())
+}
+
+/// Return an error if a delegation of the given amount, adding to an account
+/// whose delegation is currently `account_delegation`, breaks the Config's
+/// limits.
+pub fn ensure_delegation_allowed(
+  storage: &dyn Storage,
+  amount: Uint128,
+  account_delegation: Option<Uint128>,
+) -> ContractResult<()> {
+  let config = CONFIG.load(storage)?;
+  if amount < config.min_delegation {
+    return Err(ContractError::DelegationBelowMinimum {});
+  }
+  if let Some(max_liquidity) = config.max_liquidity {
+    if add(NET_LIQUIDITY.load(storage)?, amount, "net_liquidity")? > max_liquidity {
+      return Err(ContractError::LiquidityCapExceeded {});
+    }
+  }
+  if let Some(account_delegation) = account_delegation {
+    ensure_account_delegation_allowed(storage, amount, account_delegation)?;
+  }
+  Ok(())
+}
+
+/// Return an error if adding the given amount to an account whose delegation
+/// is currently `account_delegation` exceeds the Config's per-account cap.
+pub fn ensure_account_delegation_allowed(
+  storage: &dyn Storage,
+  amount: Uint128,
+  account_delegation: Uint128,
+) -> ContractResult<()> {
+  if let Some(max_account_delegation) = CONFIG.load(storage)?.max_account_delegation {
+    if add(account_delegation, amount, "account.delegation")? > max_account_delegation {
+      return Err(ContractError::AccountDelegationCapExceeded {});
+    }
+  }
   Ok(())
 }
 
@@ -89,29 +221,119 @@ pub fn is_allowed(
   })
 }
 
+/// Return an error if the given operation is currently paused.
+pub fn ensure_not_paused(
+  storage: &dyn Storage,
+  operation: Operation,
+) -> ContractResult<()> {
+  if PAUSED_OPERATIONS.load(storage)?.contains(&operation) {
+    return Err(ContractError::Paused {});
+  }
+  Ok(())
+}
+
+/// Return the amount of liquidity reserved by clients for in-flight payments
+/// or held for pending unbondings.
+pub fn get_locked_liquidity(storage: &dyn Storage) -> ContractResult<Uint128> {
+  add(
+    NET_LIQUIDITY_RESERVED.load(storage)?,
+    UNBONDING_POOL.load(storage)?.amount,
+    "locked_liquidity",
+  )
+}
+
+/// Ensure that liquidity just taken out of the house to be sent didn't come
+/// out of what's reserved by clients or held for unbondings.
+pub fn ensure_liquidity_unlocked(storage: &dyn Storage) -> ContractResult<()> {
+  if NET_LIQUIDITY.load(storage)? < get_locked_liquidity(storage)? {
+    return Err(ContractError::InsufficientLiquidity {});
+  }
+  Ok(())
+}
+
+/// Return the amount of liquidity available for new payments, reservations
+/// and withdrawals.
+pub fn get_free_liquidity(storage: &dyn Storage) -> ContractResult<Uint128> {
+  Ok(
+    NET_LIQUIDITY
+      .load(storage)?
+      .saturating_sub(get_locked_liquidity(storage)?),
+  )
+}
+
+/// Amortize the configured batch of accounts as a side effect of another
+/// operation.
 pub fn amortize(storage: &mut dyn Storage) -> ContractResult<()> {
-  let n_accounts = 1;
-  let n_retries = 2;
+  let batch_size = CONFIG.load(storage)?.amortization_batch_size;
+  amortize_n(storage, batch_size)?;
+  Ok(())
+}
+
+/// Memoize the claims of up to `n_accounts` accounts at the front of the
+/// memoization queue, moving each to the back. Returns the number of accounts
+/// processed, which is less than `n_accounts` if the whole queue was visited.
+/// At most the configured number of legacy snapshots is processed per account,
+/// leaving the rest for later batches or SettleAccount.
+pub fn amortize_n(
+  storage: &mut dyn Storage,
+  n_accounts: u32,
+) -> ContractResult<u32> {
+  // no more accounts than are queued can be visited
+  let n_accounts = n_accounts.min(MEMOIZATION_QUEUE_LEN.load(storage)?);
+  let max_snapshots = CONFIG.load(storage)?.amortization_snapshot_limit;
   let mut visited: HashSet<Addr> = HashSet::with_capacity(n_accounts as usize);
   for _ in 0..n_accounts {
-    for _ in 0..n_retries {
-      if let Some(owner) = MEMOIZATION_QUEUE.pop_front(storage)? {
-        if visited.contains(&owner) {
-          // already amorized all existing accounts
-          MEMOIZATION_QUEUE.push_front(storage, &owner)?;
-          return Ok(());
-        }
-        if let Some(mut account) = DELEGATION_ACCOUNTS.may_load(storage, owner.clone())? {
-          account.memoize_claim_amounts(storage)?;
-          visited.insert(owner.clone());
-          MEMOIZATION_QUEUE.push_back(storage, &owner)?;
-          DELEGATION_ACCOUNTS.save(storage, owner.clone(), &account)?;
-        }
-      } else {
-        // queue is empty
+    let front = MEMOIZATION_QUEUE
+      .range(storage, None, None, Order::Ascending)
+      .next()
+      .transpose()?;
+    if let Some((_, owner)) = front {
+      if visited.contains(&owner) {
+        // already amortized all existing accounts
         break;
       }
+      dequeue_memoization(storage, &owner)?;
+      if let Some(mut account) = DELEGATION_ACCOUNTS.may_load(storage, owner.clone())? {
+        account.memoize_claim_amounts(storage, Some(max_snapshots))?;
+        enqueue_memoization(storage, &owner)?;
+        visited.insert(owner);
+      }
+    } else {
+      // queue is empty
+      break;
     }
   }
+  let n_amortized = visited.len() as u32;
+  increment(storage, &N_AMORTIZED, n_amortized as u64)?;
+  Ok(n_amortized)
+}
+
+/// Add the account owner to the back of the memoization queue, unless it's
+/// already queued.
+pub fn enqueue_memoization(
+  storage: &mut dyn Storage,
+  owner: &Addr,
+) -> ContractResult<()> {
+  if MEMOIZATION_QUEUE_POSITIONS.has(storage, owner.clone()) {
+    return Ok(());
+  }
+  let position = MEMOIZATION_QUEUE_TAIL.load(storage)?;
+  MEMOIZATION_QUEUE.save(storage, position, owner)?;
+  MEMOIZATION_QUEUE_POSITIONS.save(storage, owner.clone(), &position)?;
+  MEMOIZATION_QUEUE_TAIL.save(storage, &(position + 1))?;
+  increment(storage, &MEMOIZATION_QUEUE_LEN, 1)?;
+  Ok(())
+}
+
+/// Remove the account owner from the memoization queue, wherever it is.
+pub fn dequeue_memoization(
+  storage: &mut dyn Storage,
+  owner: &Addr,
+) -> ContractResult<()> {
+  if let Some(position) = MEMOIZATION_QUEUE_POSITIONS.may_load(storage, owner.clone())? {
+    MEMOIZATION_QUEUE.remove(storage, position);
+    MEMOIZATION_QUEUE_POSITIONS.remove(storage, owner.clone());
+    decrement(storage, &MEMOIZATION_QUEUE_LEN, 1)?;
+  }
   Ok(())
 }
@@ -1,29 +1,244 @@
-use cosmwasm_std::{Addr, Api, Storage, Uint128};
+use std::fmt::Display;
+
+use cosmwasm_std::{Addr, Api, Decimal256, Storage, Uint128, Uint256};
 use cw_storage_plus::Item;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{error::ContractError, models::ContractResult};
 
-pub fn increment<T>(
+/// Numeric types stored in accumulator Items.
+pub trait Accumulator: Copy + Display + PartialOrd + DeserializeOwned + Serialize {
+  fn add_checked(
+    self,
+    other: Self,
+  ) -> Option<Self>;
+
+  fn sub_checked(
+    self,
+    other: Self,
+  ) -> Option<Self>;
+}
+
+macro_rules! impl_accumulator {
+  ($t:ty) => {
+    impl Accumulator for $t {
+      fn add_checked(
+        self,
+        other: Self,
+      ) -> Option<Self> {
+        self.checked_add(other)
+      }
+
+      fn sub_checked(
+        self,
+        other: Self,
+      ) -> Option<Self> {
+        self.checked_sub(other)
+      }
+    }
+  };
+}
+
+impl_accumulator!(u32);
+impl_accumulator!(u64);
+impl_accumulator!(u128);
+
+macro_rules! impl_fallible_accumulator {
+  ($t:ty) => {
+    impl Accumulator for $t {
+      fn add_checked(
+        self,
+        other: Self,
+      ) -> Option<Self> {
+        self.checked_add(other).ok()
+      }
+
+      fn sub_checked(
+        self,
+        other: Self,
+      ) -> Option<Self> {
+        self.checked_sub(other).ok()
+      }
+    }
+  };
+}
+
+impl_fallible_accumulator!(Uint128);
+impl_fallible_accumulator!(Decimal256);
+
+pub fn increment<T: Accumulator>(
   storage: &mut dyn Storage,
   item: &Item<T>,
   increment: T,
-) -> ContractResult<T>
-where
-  T: DeserializeOwned + Serialize + std::ops::Add<Output = T>,
-{
-  item.update(storage, |x| -> ContractResult<_> { Ok(x + increment) })
+) -> ContractResult<T> {
+  item.update(storage, |x| -> ContractResult<_> {
+    add(x, increment, &String::from_utf8_lossy(item.as_slice()))
+  })
 }
 
-pub fn decrement<T>(
+pub fn decrement<T: Accumulator>(
   storage: &mut dyn Storage,
   item: &Item<T>,
-  increment: T,
-) -> ContractResult<T>
-where
-  T: DeserializeOwned + Serialize + std::ops::Sub<Output = T>,
-{
-  item.update(storage, |x| -> ContractResult<_> { Ok(x - increment) })
+  decrement: T,
+) -> ContractResult<T> {
+  item.update(storage, |x| -> ContractResult<_> {
+    sub(x, decrement, &String::from_utf8_lossy(item.as_slice()))
+  })
+}
+
+/// Add two amounts, naming what overflowed on error.
+pub fn add<T: Accumulator>(
+  a: T,
+  b: T,
+  name: &str,
+) -> ContractResult<T> {
+  a.add_checked(b).ok_or_else(|| ContractError::Overflow {
+    name: name.to_owned(),
+  })
+}
+
+/// Subtract b from a, naming what underflowed and by how much on error.
+pub fn sub<T: Accumulator>(
+  a: T,
+  b: T,
+  name: &str,
+) -> ContractResult<T> {
+  a.sub_checked(b).ok_or_else(|| ContractError::Underflow {
+    name: name.to_owned(),
+    expected: b.to_string(),
+    actual: a.to_string(),
+  })
+}
+
+/// Compute value * numerator / denominator without panicking.
+pub fn mul_ratio(
+  value: Uint128,
+  numerator: Uint128,
+  denominator: Uint128,
+  name: &str,
+) -> ContractResult<Uint128> {
+  if denominator.is_zero() {
+    return Err(ContractError::DivisionByZero {
+      name: name.to_owned(),
+    });
+  }
+  Uint128::try_from(value.full_mul(numerator) / Uint256::from(denominator)).map_err(|_| {
+    ContractError::Overflow {
+      name: name.to_owned(),
+    }
+  })
+}
+
+/// Compute value * numerator / denominator without panicking, rounding up.
+pub fn mul_ratio_ceil(
+  value: Uint128,
+  numerator: Uint128,
+  denominator: Uint128,
+  name: &str,
+) -> ContractResult<Uint128> {
+  if denominator.is_zero() {
+    return Err(ContractError::DivisionByZero {
+      name: name.to_owned(),
+    });
+  }
+  Uint128::try_from(div_ceil(
+    value.full_mul(numerator),
+    Uint256::from(denominator),
+  ))
+  .map_err(|_| ContractError::Overflow {
+    name: name.to_owned(),
+  })
+}
+
+/// Compute amount / total as an increase in a per-share index, rounding down.
+pub fn to_index(
+  amount: Uint128,
+  total: Uint128,
+  name: &str,
+) -> ContractResult<Decimal256> {
+  let (atomics, total) = to_index_parts(amount, total, name)?;
+  Ok(Decimal256::new(atomics / total))
+}
+
+/// Compute amount / total as an increase in a per-share index, rounding up.
+pub fn to_index_ceil(
+  amount: Uint128,
+  total: Uint128,
+  name: &str,
+) -> ContractResult<Decimal256> {
+  let (atomics, total) = to_index_parts(amount, total, name)?;
+  Ok(Decimal256::new(div_ceil(atomics, total)))
+}
+
+/// Compute what an increase in a per-share index is worth to a delegation of
+/// the given amount, rounding down.
+pub fn mul_index(
+  amount: Uint128,
+  index: Decimal256,
+  name: &str,
+) -> ContractResult<Uint128> {
+  let atomics = mul_index_atomics(amount, index, name)?;
+  Uint128::try_from(atomics / Decimal256::one().atomics()).map_err(|_| ContractError::Overflow {
+    name: name.to_owned(),
+  })
+}
+
+/// Compute what an increase in a per-share index is worth to a delegation of
+/// the given amount, rounding up.
+pub fn mul_index_ceil(
+  amount: Uint128,
+  index: Decimal256,
+  name: &str,
+) -> ContractResult<Uint128> {
+  let atomics = mul_index_atomics(amount, index, name)?;
+  Uint128::try_from(div_ceil(atomics, Decimal256::one().atomics())).map_err(|_| {
+    ContractError::Overflow {
+      name: name.to_owned(),
+    }
+  })
+}
+
+fn to_index_parts(
+  amount: Uint128,
+  total: Uint128,
+  name: &str,
+) -> ContractResult<(Uint256, Uint256)> {
+  if total.is_zero() {
+    return Err(ContractError::DivisionByZero {
+      name: name.to_owned(),
+    });
+  }
+  let atomics = Uint256::from(amount)
+    .checked_mul(Decimal256::one().atomics())
+    .map_err(|_| ContractError::Overflow {
+      name: name.to_owned(),
+    })?;
+  Ok((atomics, Uint256::from(total)))
+}
+
+fn mul_index_atomics(
+  amount: Uint128,
+  index: Decimal256,
+  name: &str,
+) -> ContractResult<Uint256> {
+  Uint256::from(amount)
+    .checked_mul(index.atomics())
+    .map_err(|_| ContractError::Overflow {
+      name: name.to_owned(),
+    })
+}
+
+/// Divide a by a non-zero b, rounding up.
+fn div_ceil(
+  a: Uint256,
+  b: Uint256,
+) -> Uint256 {
+  let quotient = a / b;
+  if (a % b).is_zero() {
+    quotient
+  } else {
+    quotient + Uint256::one()
+  }
 }
 
 pub fn mul_pct(
@@ -33,6 +248,21 @@ pub fn mul_pct(
   total.multiply_ratio(pct, Uint128::from(1000u128))
 }
 
+/// Parse a "major.minor.patch" version string into a tuple for comparison.
+pub fn parse_version(version: &str) -> ContractResult<(u64, u64, u64)> {
+  let parts: Vec<u64> = version
+    .split('.')
+    .map(|part| part.parse::<u64>())
+    .collect::<Result<_, _>>()
+    .map_err(|_| ContractError::InvalidVersion {})?;
+
+  if parts.len() != 3 {
+    return Err(ContractError::InvalidVersion {});
+  }
+
+  Ok((parts[0], parts[1], parts[2]))
+}
+
 pub fn validate_addr(
   api: &dyn Api,
   addr: &Addr,
@@ -41,3 +271,11 @@ pub fn validate_addr(
     .addr_validate(addr.as_str())
     .map_err(|_| ContractError::InvalidAddress {})
 }
+
+pub const DEFAULT_PAGE_LIMIT: u32 = 25;
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Resolve the page size requested by a paginated query.
+pub fn get_page_limit(limit: Option<u32>) -> usize {
+  limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT) as usize
+}
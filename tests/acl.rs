@@ -0,0 +1,144 @@
+mod common;
+
+use common::*;
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_house_staking::{
+  models::Operation,
+  msg::{ExecuteMsg, InstantiateMsg},
+};
+use cw_lib::models::{Owner, Token};
+use cw_multi_test::{App, Executor};
+
+const ADMIN: &str = "admin";
+
+fn setup() -> (App, Addr) {
+  let mut app = native_app();
+  let acl = instantiate_acl(&mut app, &[(ADMIN, &["set_client", "pause", "unpause"])]);
+  let house = instantiate_house(
+    &mut app,
+    &InstantiateMsg {
+      owner: Owner::Acl(acl),
+      token: Token::Native {
+        denom: DENOM.to_owned(),
+      },
+      fee: None,
+      config: None,
+    },
+  );
+  (app, house)
+}
+
+#[test]
+fn acl_gates_set_client() {
+  let (mut app, house) = setup();
+  let set_client_msg = ExecuteMsg::SetClient {
+    address: Addr::unchecked(CLIENT),
+    metadata: None,
+  };
+
+  let err = app
+    .execute_contract(Addr::unchecked(ALICE), house.clone(), &set_client_msg, &[])
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "NotAuthorized");
+  assert_eq!(
+    select(&app, &house, None).stats.unwrap().n_client_accounts,
+    0
+  );
+
+  app
+    .execute_contract(Addr::unchecked(ADMIN), house.clone(), &set_client_msg, &[])
+    .unwrap();
+
+  assert_eq!(
+    select(&app, &house, None).stats.unwrap().n_client_accounts,
+    1
+  );
+}
+
+#[test]
+fn acl_gates_pause() {
+  let (mut app, house) = setup();
+
+  app
+    .execute_contract(
+      Addr::unchecked(ADMIN),
+      house.clone(),
+      &ExecuteMsg::SetClient {
+        address: Addr::unchecked(CLIENT),
+        metadata: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: Uint128::from(1000u128),
+        profit: Uint128::zero(),
+        mint_shares: None,
+        beneficiary: None,
+      },
+      &coins(1000, DENOM),
+    )
+    .unwrap();
+
+  let pause_msg = ExecuteMsg::Pause {
+    operations: vec![Operation::SendPayment],
+  };
+  let send_payment_msg = ExecuteMsg::SendPayment {
+    recipient: Addr::unchecked(CAROL),
+    amount: Uint128::from(100u128),
+  };
+
+  let err = app
+    .execute_contract(Addr::unchecked(ALICE), house.clone(), &pause_msg, &[])
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "NotAuthorized");
+
+  app
+    .execute_contract(Addr::unchecked(ADMIN), house.clone(), &pause_msg, &[])
+    .unwrap();
+
+  assert_eq!(
+    select(&app, &house, None).status.unwrap().paused_operations,
+    vec![Operation::SendPayment]
+  );
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &send_payment_msg,
+      &[],
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "Paused");
+
+  app
+    .execute_contract(
+      Addr::unchecked(ADMIN),
+      house.clone(),
+      &ExecuteMsg::Unpause {
+        operations: vec![Operation::SendPayment],
+      },
+      &[],
+    )
+    .unwrap();
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &send_payment_msg,
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(native_balance(&app, CAROL), 100);
+}
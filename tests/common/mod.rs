@@ -0,0 +1,374 @@
+#![allow(dead_code)]
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{coins, from_binary, Addr, Deps, Empty, OwnedDeps, Timestamp, Uint128};
+use cw2::set_contract_version;
+use cw20::{BalanceResponse, Cw20Coin, Cw20QueryMsg};
+use cw_house_staking::{
+  models::{Delegation, Snapshot},
+  msg::{InstantiateMsg, InvariantsReport, InvariantsResponse, QueryMsg, SelectResponse},
+  state::{
+    self, CLIENT_ACCOUNTS_LEN, DELEGATION_ACCOUNTS_LEN, GROWTH_DELEGATIONS,
+    GROWTH_DELEGATIONS_SEQ_NO, GROWTH_DELEGATOR_COUNT, NET_GROWTH_DELEGATION, NET_LIQUIDITY,
+    NET_PROFIT, NET_PROFIT_DELEGATION, PROFIT_DELEGATIONS, PROFIT_DELEGATIONS_SEQ_NO,
+    PROFIT_DELEGATOR_COUNT, SNAPSHOTS, SNAPSHOTS_INDEX, SNAPSHOTS_LEN,
+  },
+};
+use cw_lib::models::{Owner, Token};
+use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+use cw_storage_plus::{Deque, Item, Map};
+
+pub const DENOM: &str = "ujuno";
+pub const INITIAL_BALANCE: u128 = 10_000;
+
+pub const OWNER: &str = "owner";
+pub const ALICE: &str = "alice";
+pub const BOB: &str = "bob";
+pub const CAROL: &str = "carol";
+pub const CLIENT: &str = "client";
+
+pub fn house_contract() -> Box<dyn Contract<Empty>> {
+  Box::new(
+    ContractWrapper::new(
+      cw_house_staking::contract::execute,
+      cw_house_staking::contract::instantiate,
+      cw_house_staking::contract::query,
+    )
+    .with_reply(cw_house_staking::contract::reply),
+  )
+}
+
+pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
+  Box::new(ContractWrapper::new(
+    cw20_base::contract::execute,
+    cw20_base::contract::instantiate,
+    cw20_base::contract::query,
+  ))
+}
+
+/// App with native token balances for delegators and the client.
+pub fn native_app() -> App {
+  AppBuilder::new().build(|router, _, storage| {
+    for addr in [ALICE, BOB, CLIENT] {
+      router
+        .bank
+        .init_balance(
+          storage,
+          &Addr::unchecked(addr),
+          coins(INITIAL_BALANCE, DENOM),
+        )
+        .unwrap();
+    }
+  })
+}
+
+pub fn instantiate_house(
+  app: &mut App,
+  msg: &InstantiateMsg,
+) -> Addr {
+  let code_id = app.store_code(house_contract());
+  app
+    .instantiate_contract(code_id, Addr::unchecked(OWNER), msg, &[], "house", None)
+    .unwrap()
+}
+
+/// Instantiate a cw20-base token with initial balances for delegators and the
+/// client.
+pub fn instantiate_cw20(
+  app: &mut App,
+  symbol: &str,
+) -> Addr {
+  let code_id = app.store_code(cw20_contract());
+  app
+    .instantiate_contract(
+      code_id,
+      Addr::unchecked(OWNER),
+      &cw20_base::msg::InstantiateMsg {
+        name: format!("{} Token", symbol),
+        symbol: symbol.to_owned(),
+        decimals: 6,
+        initial_balances: [ALICE, BOB, CLIENT]
+          .iter()
+          .map(|addr| Cw20Coin {
+            address: addr.to_string(),
+            amount: Uint128::from(INITIAL_BALANCE),
+          })
+          .collect(),
+        mint: None,
+        marketing: None,
+      },
+      &[],
+      symbol,
+      None,
+    )
+    .unwrap()
+}
+
+pub fn select(
+  app: &App,
+  house: &Addr,
+  wallet: Option<&str>,
+) -> SelectResponse {
+  app
+    .wrap()
+    .query_wasm_smart(
+      house,
+      &QueryMsg::Select {
+        fields: None,
+        wallet: wallet.map(Addr::unchecked),
+      },
+    )
+    .unwrap()
+}
+
+/// Walk every page of the invariants query, returning the final report.
+pub fn check_invariants(
+  app: &App,
+  house: &Addr,
+) -> InvariantsReport {
+  let mut start_after = None;
+  let mut totals = None;
+  loop {
+    let resp: InvariantsResponse = app
+      .wrap()
+      .query_wasm_smart(
+        house,
+        &QueryMsg::Invariants {
+          start_after,
+          limit: Some(1),
+          totals,
+        },
+      )
+      .unwrap();
+    if let Some(report) = resp.report {
+      return report;
+    }
+    start_after = resp.next;
+    totals = Some(resp.totals);
+  }
+}
+
+pub fn native_balance(
+  app: &App,
+  addr: &str,
+) -> u128 {
+  app.wrap().query_balance(addr, DENOM).unwrap().amount.u128()
+}
+
+pub fn cw20_balance(
+  app: &App,
+  cw20: &Addr,
+  addr: &str,
+) -> u128 {
+  let resp: BalanceResponse = app
+    .wrap()
+    .query_wasm_smart(
+      cw20,
+      &Cw20QueryMsg::Balance {
+        address: addr.to_owned(),
+      },
+    )
+    .unwrap();
+  resp.balance.u128()
+}
+
+pub fn acl_contract() -> Box<dyn Contract<Empty>> {
+  Box::new(ContractWrapper::new(
+    cw_acl::contract::execute,
+    cw_acl::contract::instantiate,
+    cw_acl::contract::query,
+  ))
+}
+
+/// Instantiate a cw-acl contract owned by OWNER, allowing each of the given
+/// principals to perform the given house actions.
+pub fn instantiate_acl(
+  app: &mut App,
+  grants: &[(&str, &[&str])],
+) -> Addr {
+  let code_id = app.store_code(acl_contract());
+  let acl = app
+    .instantiate_contract(
+      code_id,
+      Addr::unchecked(OWNER),
+      &cw_acl::msg::InstantiateMsg {
+        name: None,
+        description: None,
+        owner: None,
+      },
+      &[],
+      "acl",
+      None,
+    )
+    .unwrap();
+  for (principal, actions) in grants {
+    for action in actions.iter() {
+      app
+        .execute_contract(
+          Addr::unchecked(OWNER),
+          acl.clone(),
+          &cw_acl::msg::ExecuteMsg::Allow {
+            principal: Addr::unchecked(*principal),
+            action: action.to_string(),
+          },
+          &[],
+        )
+        .unwrap();
+    }
+  }
+  acl
+}
+
+/// DelegationAccount as saved by v0.0.3
+#[cw_serde]
+struct LegacyDelegationAccount {
+  owner: Addr,
+  created_at: Timestamp,
+  memoized_profit: Uint128,
+  memoized_growth: Uint128,
+  memoized_loss: Uint128,
+}
+
+/// Mock dependencies holding the storage a v0.0.3 house would have left
+/// behind: ALICE delegated 600 to the growth pool and another 300 after two
+/// snapshots, BOB delegated 400 to the profit pool, and six snapshots of 100 in
+/// revenue were taken, the fourth of them also recording a loss of 90. ALICE is
+/// owed 396 in growth and 90 in losses, and BOB 200 in profit.
+pub fn legacy_house() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+  let mut deps = mock_dependencies();
+  let storage = deps.as_mut().storage;
+
+  set_contract_version(storage, "crates.io:house-staking-contract", "0.0.3").unwrap();
+
+  state::OWNER
+    .save(storage, &Owner::Address(Addr::unchecked(OWNER)))
+    .unwrap();
+  state::TOKEN
+    .save(
+      storage,
+      &Token::Native {
+        denom: DENOM.to_owned(),
+      },
+    )
+    .unwrap();
+
+  // items whose type or purpose has since changed
+  Item::<u32>::new("net_pct_liquidity_allocated")
+    .save(storage, &0)
+    .unwrap();
+  Item::<Uint128>::new("snapshot_seq_no")
+    .save(storage, &Uint128::from(5u128))
+    .unwrap();
+  CLIENT_ACCOUNTS_LEN.save(storage, &0).unwrap();
+
+  let alice = Addr::unchecked(ALICE);
+  let bob = Addr::unchecked(BOB);
+
+  for (i, growth_delegation) in [600u128, 600, 900, 900, 900, 900].iter().enumerate() {
+    SNAPSHOTS
+      .save(
+        storage,
+        i as u128,
+        &Snapshot {
+          seq_no: Uint128::from(i as u128),
+          claims_remaining: 2,
+          growth_delegation: Uint128::from(*growth_delegation),
+          profit_delegation: Uint128::from(400u128),
+          growth: Uint128::from(100u128),
+          loss: Uint128::from(if i == 3 { 90u128 } else { 0 }),
+        },
+      )
+      .unwrap();
+  }
+  SNAPSHOTS_LEN.save(storage, &6).unwrap();
+  SNAPSHOTS_INDEX
+    .save(storage, &Uint128::from(6u128))
+    .unwrap();
+
+  for (seq_no, amount, i_snapshot) in [(0u128, 600u128, 0u128), (1, 900, 2)] {
+    GROWTH_DELEGATIONS
+      .save(
+        storage,
+        (alice.clone(), seq_no),
+        &Delegation {
+          owner: alice.clone(),
+          amount: Uint128::from(amount),
+          i_snapshot: Uint128::from(i_snapshot),
+        },
+      )
+      .unwrap();
+  }
+  GROWTH_DELEGATIONS_SEQ_NO
+    .save(storage, alice.clone(), &1)
+    .unwrap();
+
+  PROFIT_DELEGATIONS
+    .save(
+      storage,
+      (bob.clone(), 0),
+      &Delegation {
+        owner: bob.clone(),
+        amount: Uint128::from(400u128),
+        i_snapshot: Uint128::zero(),
+      },
+    )
+    .unwrap();
+  PROFIT_DELEGATIONS_SEQ_NO
+    .save(storage, bob.clone(), &0)
+    .unwrap();
+
+  // accounts were saved without any of the fields added since
+  for owner in [&alice, &bob] {
+    Map::<Addr, LegacyDelegationAccount>::new("delegation_accounts")
+      .save(
+        storage,
+        owner.clone(),
+        &LegacyDelegationAccount {
+          owner: owner.clone(),
+          created_at: mock_env().block.time,
+          memoized_profit: Uint128::zero(),
+          memoized_growth: Uint128::zero(),
+          memoized_loss: Uint128::zero(),
+        },
+      )
+      .unwrap();
+    Deque::<Addr>::new("memoization_queue")
+      .push_back(storage, owner)
+      .unwrap();
+  }
+  DELEGATION_ACCOUNTS_LEN.save(storage, &2).unwrap();
+  GROWTH_DELEGATOR_COUNT.save(storage, &1).unwrap();
+  PROFIT_DELEGATOR_COUNT.save(storage, &1).unwrap();
+
+  NET_GROWTH_DELEGATION
+    .save(storage, &Uint128::from(900u128))
+    .unwrap();
+  NET_PROFIT_DELEGATION
+    .save(storage, &Uint128::from(400u128))
+    .unwrap();
+  NET_LIQUIDITY
+    .save(storage, &Uint128::from(1206u128))
+    .unwrap();
+  NET_PROFIT.save(storage, &Uint128::from(200u128)).unwrap();
+
+  deps
+}
+
+pub fn mock_select(
+  deps: Deps,
+  wallet: Option<&str>,
+) -> SelectResponse {
+  from_binary(
+    &cw_house_staking::contract::query(
+      deps,
+      mock_env(),
+      QueryMsg::Select {
+        fields: None,
+        wallet: wallet.map(Addr::unchecked),
+      },
+    )
+    .unwrap(),
+  )
+  .unwrap()
+}
@@ -0,0 +1,314 @@
+mod common;
+
+use common::*;
+use cosmwasm_std::{to_binary, Addr, Uint128};
+use cw20::Cw20ExecuteMsg;
+use cw_house_staking::{
+  models::{FeeConfig, Operation},
+  msg::{ExecuteMsg, InstantiateMsg, ReceiveMsg},
+};
+use cw_lib::models::{Owner, Token};
+use cw_multi_test::{App, Executor};
+
+const TREASURY: &str = "treasury";
+
+fn setup() -> (App, Addr, Addr) {
+  let mut app = App::default();
+  let cw20 = instantiate_cw20(&mut app, "HOUSE");
+  let house = instantiate_house(
+    &mut app,
+    &InstantiateMsg {
+      owner: Owner::Address(Addr::unchecked(OWNER)),
+      token: Token::Cw20 {
+        address: cw20.clone(),
+      },
+      fee: Some(FeeConfig {
+        rate: 1000,
+        recipient: Some(Addr::unchecked(TREASURY)),
+      }),
+      config: None,
+    },
+  );
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::SetClient {
+        address: Addr::unchecked(CLIENT),
+        metadata: None,
+      },
+      &[],
+    )
+    .unwrap();
+  (app, cw20, house)
+}
+
+fn send(
+  app: &mut App,
+  cw20: &Addr,
+  house: &Addr,
+  sender: &str,
+  amount: u128,
+  msg: &ReceiveMsg,
+) {
+  app
+    .execute_contract(
+      Addr::unchecked(sender),
+      cw20.clone(),
+      &Cw20ExecuteMsg::Send {
+        contract: house.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(msg).unwrap(),
+      },
+      &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn lifecycle_through_receive_hook() {
+  let (mut app, cw20, house) = setup();
+
+  send(
+    &mut app,
+    &cw20,
+    &house,
+    ALICE,
+    1000,
+    &ReceiveMsg::Delegate {
+      growth: Uint128::from(1000u128),
+      profit: Uint128::zero(),
+      mint_shares: None,
+      beneficiary: None,
+    },
+  );
+
+  // 10% of the revenue is taken as a fee
+  send(
+    &mut app,
+    &cw20,
+    &house,
+    CLIENT,
+    500,
+    &ReceiveMsg::ReceivePayment { sender: None },
+  );
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::SendPayment {
+        recipient: Addr::unchecked(CAROL),
+        amount: Uint128::from(200u128),
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(cw20_balance(&app, &cw20, CAROL), 200);
+  assert_eq!(cw20_balance(&app, &cw20, house.as_str()), 1300);
+
+  let resp = select(&app, &house, Some(ALICE));
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(1250u128)));
+  assert_eq!(resp.total_profit_claimable, Some(Uint128::zero()));
+
+  let stats = resp.stats.unwrap();
+  assert_eq!(stats.lifetime_fees, Uint128::from(50u128));
+  assert_eq!(stats.fees_claimable, Uint128::from(50u128));
+
+  let account = resp.account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(1000u128));
+  assert_eq!(account.growth_claimable, Uint128::from(450u128));
+  assert_eq!(account.loss_claimable, Uint128::from(200u128));
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::ClaimFees {},
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(cw20_balance(&app, &cw20, TREASURY), 50);
+
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::Withdraw {
+        growth: None,
+        profit: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(
+    cw20_balance(&app, &cw20, ALICE),
+    INITIAL_BALANCE - 1000 + 1250
+  );
+  assert_eq!(cw20_balance(&app, &cw20, CLIENT), INITIAL_BALANCE - 500);
+  assert_eq!(cw20_balance(&app, &cw20, house.as_str()), 0);
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.total_liquidity, Some(Uint128::zero()));
+  assert_eq!(resp.stats.unwrap().fees_claimable, Uint128::zero());
+}
+
+#[test]
+fn delegate_with_allowance() {
+  let (mut app, cw20, house) = setup();
+
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      cw20.clone(),
+      &Cw20ExecuteMsg::IncreaseAllowance {
+        spender: house.to_string(),
+        amount: Uint128::from(400u128),
+        expires: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: Uint128::from(300u128),
+        profit: Uint128::from(100u128),
+        mint_shares: None,
+        beneficiary: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(cw20_balance(&app, &cw20, BOB), INITIAL_BALANCE - 400);
+  assert_eq!(cw20_balance(&app, &cw20, house.as_str()), 400);
+
+  let pools = select(&app, &house, None).pools.unwrap();
+  assert_eq!(pools.growth, Uint128::from(300u128));
+  assert_eq!(pools.profit, Uint128::from(100u128));
+}
+
+#[test]
+fn receive_rejects_other_tokens() {
+  let (mut app, _, house) = setup();
+  let other_cw20 = instantiate_cw20(&mut app, "OTHER");
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      other_cw20.clone(),
+      &Cw20ExecuteMsg::Send {
+        contract: house.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&ReceiveMsg::Delegate {
+          growth: Uint128::from(1000u128),
+          profit: Uint128::zero(),
+          mint_shares: None,
+          beneficiary: None,
+        })
+        .unwrap(),
+      },
+      &[],
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "InvalidToken");
+  assert_eq!(cw20_balance(&app, &other_cw20, ALICE), INITIAL_BALANCE);
+}
+
+#[test]
+fn delegate_for_beneficiary() {
+  let (mut app, cw20, house) = setup();
+
+  send(
+    &mut app,
+    &cw20,
+    &house,
+    ALICE,
+    500,
+    &ReceiveMsg::Delegate {
+      growth: Uint128::from(500u128),
+      profit: Uint128::zero(),
+      mint_shares: None,
+      beneficiary: Some(Addr::unchecked(CAROL)),
+    },
+  );
+
+  assert_eq!(cw20_balance(&app, &cw20, ALICE), INITIAL_BALANCE - 500);
+
+  let resp = select(&app, &house, Some(CAROL));
+  assert_eq!(resp.stats.unwrap().n_delegation_accounts, 1);
+  assert_eq!(
+    resp.account.unwrap().growth_delegation,
+    Uint128::from(500u128)
+  );
+
+  let account = select(&app, &house, Some(ALICE)).account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::zero());
+}
+
+#[test]
+fn pause_claim_fees() {
+  let (mut app, cw20, house) = setup();
+
+  send(
+    &mut app,
+    &cw20,
+    &house,
+    CLIENT,
+    500,
+    &ReceiveMsg::ReceivePayment { sender: None },
+  );
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::Pause {
+        operations: vec![Operation::ClaimFees],
+      },
+      &[],
+    )
+    .unwrap();
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::ClaimFees {},
+      &[],
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "Paused");
+  assert_eq!(cw20_balance(&app, &cw20, TREASURY), 0);
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::Unpause {
+        operations: vec![Operation::ClaimFees],
+      },
+      &[],
+    )
+    .unwrap();
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::ClaimFees {},
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(cw20_balance(&app, &cw20, TREASURY), 50);
+}
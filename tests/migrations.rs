@@ -0,0 +1,368 @@
+mod common;
+
+use common::*;
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Addr, Deps, DepsMut, Order, Response, Storage, Uint128};
+use cw2::{get_contract_version, set_contract_version};
+use cw_house_staking::{
+  contract::{execute, migrate, query},
+  error::ContractError,
+  models::{Config, PoolIndices, Snapshot},
+  msg::{ExecuteMsg, MigrateMsg, QueryMsg, SnapshotEntry, SnapshotsResponse},
+  state::{
+    CONFIG, DELEGATION_ACCOUNTS, GROWTH_DELEGATIONS, MEMOIZATION_QUEUE, MEMOIZATION_QUEUE_LEN,
+    NET_GROWTH_DELEGATION, NET_LIQUIDITY, NET_LIQUIDITY_RESERVED, NET_PROFIT,
+    NET_PROFIT_DELEGATION, POOL_INDICES, PROFIT_DELEGATIONS, SNAPSHOTS, SNAPSHOTS_LEN,
+  },
+};
+use cw_storage_plus::Deque;
+
+fn net_totals(storage: &dyn Storage) -> [u128; 4] {
+  [
+    NET_GROWTH_DELEGATION.load(storage).unwrap().u128(),
+    NET_PROFIT_DELEGATION.load(storage).unwrap().u128(),
+    NET_LIQUIDITY.load(storage).unwrap().u128(),
+    NET_PROFIT.load(storage).unwrap().u128(),
+  ]
+}
+
+fn migrate_legacy_house(
+  deps: DepsMut,
+  config: Option<Config>,
+) {
+  migrate(
+    deps,
+    mock_env(),
+    MigrateMsg {
+      fee: None,
+      unbonding_period: None,
+      config,
+    },
+  )
+  .unwrap();
+}
+
+fn settle_account(
+  deps: DepsMut,
+  owner: &str,
+  max_snapshots: u32,
+) -> Response {
+  execute(
+    deps,
+    mock_env(),
+    mock_info(owner, &[]),
+    ExecuteMsg::SettleAccount { max_snapshots },
+  )
+  .unwrap()
+}
+
+fn attr_value(
+  resp: &Response,
+  key: &str,
+) -> String {
+  resp
+    .attributes
+    .iter()
+    .find(|a| a.key == key)
+    .unwrap()
+    .value
+    .clone()
+}
+
+fn snapshots(storage: &dyn Storage) -> Vec<(u128, Snapshot)> {
+  SNAPSHOTS
+    .range(storage, None, None, Order::Ascending)
+    .collect::<Result<_, _>>()
+    .unwrap()
+}
+
+fn query_snapshots(
+  deps: Deps,
+  start_after: Option<u128>,
+  limit: Option<u32>,
+  order: Option<Order>,
+) -> Vec<SnapshotEntry> {
+  let resp: SnapshotsResponse = from_binary(
+    &query(
+      deps,
+      mock_env(),
+      QueryMsg::Snapshots {
+        start_after: start_after.map(Uint128::from),
+        limit,
+        order,
+      },
+    )
+    .unwrap(),
+  )
+  .unwrap();
+  resp.snapshots
+}
+
+fn indices(entries: &[SnapshotEntry]) -> Vec<u128> {
+  entries.iter().map(|entry| entry.index.u128()).collect()
+}
+
+fn assert_claimable(deps: Deps) {
+  let alice = mock_select(deps, Some(ALICE)).account.unwrap();
+  assert_eq!(alice.growth_delegation, Uint128::from(900u128));
+  assert_eq!(alice.growth_claimable, Uint128::from(396u128));
+  assert_eq!(alice.loss_claimable, Uint128::from(90u128));
+
+  let bob = mock_select(deps, Some(BOB)).account.unwrap();
+  assert_eq!(bob.profit_delegation, Uint128::from(400u128));
+  assert_eq!(bob.profit_claimable, Uint128::from(200u128));
+}
+
+fn try_migrate(
+  deps: DepsMut,
+  contract: &str,
+  version: &str,
+) -> Result<Response, ContractError> {
+  set_contract_version(deps.storage, contract, version).unwrap();
+  migrate(
+    deps,
+    mock_env(),
+    MigrateMsg {
+      fee: None,
+      unbonding_period: None,
+      config: None,
+    },
+  )
+}
+
+#[test]
+fn migrate_refuses_other_contracts() {
+  let mut deps = legacy_house();
+  let err = try_migrate(deps.as_mut(), "crates.io:other-contract", "0.0.3").unwrap_err();
+  assert!(matches!(err, ContractError::InvalidContractName {}));
+  assert_eq!(
+    get_contract_version(&deps.storage).unwrap().contract,
+    "crates.io:other-contract"
+  );
+}
+
+#[test]
+fn migrate_refuses_older_versions() {
+  let mut deps = legacy_house();
+  let err = try_migrate(deps.as_mut(), "crates.io:house-staking-contract", "9.9.9").unwrap_err();
+  assert!(matches!(err, ContractError::CannotMigrateToOlderVersion {}));
+  assert_eq!(
+    get_contract_version(&deps.storage).unwrap().version,
+    "9.9.9"
+  );
+}
+
+#[test]
+fn migrate_v0_0_3_storage() {
+  let mut deps = legacy_house();
+  migrate_legacy_house(deps.as_mut(), None);
+
+  assert_eq!(
+    get_contract_version(&deps.storage).unwrap().version,
+    env!("CARGO_PKG_VERSION")
+  );
+  assert_eq!(CONFIG.load(&deps.storage).unwrap(), Config::default());
+  assert_eq!(
+    NET_LIQUIDITY_RESERVED.load(&deps.storage).unwrap(),
+    Uint128::zero()
+  );
+  assert_eq!(
+    POOL_INDICES.load(&deps.storage).unwrap(),
+    PoolIndices::default()
+  );
+  assert!(deps.storage.get(b"snapshot_seq_no").is_none());
+
+  // the legacy queue is drained into its replacement, in the same order
+  assert_eq!(
+    Deque::<Addr>::new("memoization_queue")
+      .len(&deps.storage)
+      .unwrap(),
+    0
+  );
+  assert_eq!(MEMOIZATION_QUEUE_LEN.load(&deps.storage).unwrap(), 2);
+  assert_eq!(
+    MEMOIZATION_QUEUE
+      .range(&deps.storage, None, None, Order::Ascending)
+      .map(|entry| entry.unwrap().1)
+      .collect::<Vec<_>>(),
+    vec![Addr::unchecked(ALICE), Addr::unchecked(BOB)]
+  );
+
+  // accounts saved without the newer fields load with their defaults
+  let alice = DELEGATION_ACCOUNTS
+    .load(&deps.storage, Addr::unchecked(ALICE))
+    .unwrap();
+  assert_eq!(alice.growth_delegation, Uint128::zero());
+  assert_eq!(alice.indices, PoolIndices::default());
+  assert_claimable(deps.as_ref());
+}
+
+#[test]
+fn migrate_legacy_delegations() {
+  let mut deps = legacy_house();
+  let totals = net_totals(&deps.storage);
+
+  migrate_legacy_house(deps.as_mut(), None);
+
+  // migrating leaves the legacy records in place, still claimable
+  assert_claimable(deps.as_ref());
+  assert_eq!(net_totals(&deps.storage), totals);
+  assert_eq!(
+    mock_select(deps.as_ref(), None).stats.unwrap().n_snapshots,
+    6
+  );
+  assert_eq!(
+    GROWTH_DELEGATIONS
+      .prefix(Addr::unchecked(ALICE))
+      .keys(&deps.storage, None, None, Order::Ascending)
+      .count(),
+    2
+  );
+
+  for owner in [ALICE, BOB] {
+    settle_account(deps.as_mut(), owner, u32::MAX);
+  }
+
+  // settling converts them onto the accounts without changing what's owed
+  assert_claimable(deps.as_ref());
+  assert_eq!(net_totals(&deps.storage), totals);
+  assert_eq!(SNAPSHOTS_LEN.load(&deps.storage).unwrap(), 0);
+
+  let alice = DELEGATION_ACCOUNTS
+    .load(&deps.storage, Addr::unchecked(ALICE))
+    .unwrap();
+  assert_eq!(alice.growth_delegation, Uint128::from(900u128));
+  assert_eq!(alice.memoized_growth, Uint128::from(396u128));
+  assert_eq!(alice.memoized_loss, Uint128::from(90u128));
+  assert_eq!(
+    GROWTH_DELEGATIONS
+      .prefix(Addr::unchecked(ALICE))
+      .keys(&deps.storage, None, None, Order::Ascending)
+      .count(),
+    0
+  );
+
+  let bob = DELEGATION_ACCOUNTS
+    .load(&deps.storage, Addr::unchecked(BOB))
+    .unwrap();
+  assert_eq!(bob.profit_delegation, Uint128::from(400u128));
+  assert_eq!(bob.memoized_profit, Uint128::from(200u128));
+  assert_eq!(
+    PROFIT_DELEGATIONS
+      .prefix(Addr::unchecked(BOB))
+      .keys(&deps.storage, None, None, Order::Ascending)
+      .count(),
+    0
+  );
+}
+
+#[test]
+fn settle_account_in_batches() {
+  let mut batched = legacy_house();
+  let mut unbounded = legacy_house();
+  migrate_legacy_house(batched.as_mut(), None);
+  migrate_legacy_house(unbounded.as_mut(), None);
+
+  // ALICE's two records span six snapshots, processed two at a time
+  for (n_snapshots, is_settled) in [("2", "false"), ("2", "false"), ("2", "true")] {
+    let resp = settle_account(batched.as_mut(), ALICE, 2);
+    assert_eq!(attr_value(&resp, "n_snapshots"), n_snapshots);
+    assert_eq!(attr_value(&resp, "is_settled"), is_settled);
+    assert_claimable(batched.as_ref());
+  }
+
+  let resp = settle_account(unbounded.as_mut(), ALICE, u32::MAX);
+  assert_eq!(attr_value(&resp, "n_snapshots"), "6");
+  assert_eq!(attr_value(&resp, "is_settled"), "true");
+
+  assert_eq!(
+    DELEGATION_ACCOUNTS
+      .load(&batched.storage, Addr::unchecked(ALICE))
+      .unwrap(),
+    DELEGATION_ACCOUNTS
+      .load(&unbounded.storage, Addr::unchecked(ALICE))
+      .unwrap()
+  );
+  assert_eq!(snapshots(&batched.storage), snapshots(&unbounded.storage));
+  assert_eq!(net_totals(&batched.storage), net_totals(&unbounded.storage));
+
+  // once settled, there's nothing left to process
+  let resp = settle_account(batched.as_mut(), ALICE, 2);
+  assert_eq!(attr_value(&resp, "n_snapshots"), "0");
+  assert_eq!(attr_value(&resp, "is_settled"), "true");
+}
+
+#[test]
+fn amortize_respects_snapshot_limit() {
+  let mut deps = legacy_house();
+  migrate_legacy_house(
+    deps.as_mut(),
+    Some(Config {
+      amortization_snapshot_limit: 3,
+      ..Config::default()
+    }),
+  );
+
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CAROL, &[]),
+    ExecuteMsg::Amortize { limit: Some(1) },
+  )
+  .unwrap();
+
+  // ALICE's first record is done with, and her second resumes a snapshot in
+  let records: Vec<_> = GROWTH_DELEGATIONS
+    .prefix(Addr::unchecked(ALICE))
+    .range(&deps.storage, None, None, Order::Ascending)
+    .collect::<Result<_, _>>()
+    .unwrap();
+  assert_eq!(records.len(), 1);
+  assert_eq!(records[0].1.i_snapshot, Uint128::from(3u128));
+  assert_claimable(deps.as_ref());
+}
+
+#[test]
+fn query_snapshots_in_pages() {
+  let mut deps = legacy_house();
+  migrate_legacy_house(
+    deps.as_mut(),
+    Some(Config {
+      snapshot_query_limit: 4,
+      ..Config::default()
+    }),
+  );
+  let deps = deps.as_ref();
+
+  // newest first by default, up to the configured limit
+  let page = query_snapshots(deps, None, None, None);
+  assert_eq!(indices(&page), vec![5, 4, 3, 2]);
+  let page = query_snapshots(deps, Some(2), None, None);
+  assert_eq!(indices(&page), vec![1, 0]);
+
+  // oldest first, resuming after the last index of each page
+  let mut pages = vec![];
+  let mut start_after = None;
+  loop {
+    let page = query_snapshots(deps, start_after, Some(2), Some(Order::Ascending));
+    if page.is_empty() {
+      break;
+    }
+    start_after = page.last().map(|entry| entry.index.u128());
+    pages.push(indices(&page));
+  }
+  assert_eq!(pages, vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+
+  // limits are clamped between one and the configured max
+  let page = query_snapshots(deps, None, Some(100), Some(Order::Ascending));
+  assert_eq!(indices(&page), vec![0, 1, 2, 3]);
+  let page = query_snapshots(deps, None, Some(0), None);
+  assert_eq!(indices(&page), vec![5]);
+
+  let page = query_snapshots(deps, Some(4), Some(1), None);
+  assert_eq!(page[0].index, Uint128::from(3u128));
+  assert_eq!(page[0].snapshot.seq_no, Uint128::from(3u128));
+  assert_eq!(page[0].snapshot.claims_remaining, 2);
+  assert_eq!(page[0].snapshot.growth_delegation, Uint128::from(900u128));
+  assert_eq!(page[0].snapshot.loss, Uint128::from(90u128));
+}
@@ -0,0 +1,1547 @@
+mod common;
+
+use common::*;
+use cosmwasm_std::{coins, Addr, Decimal256, Uint128};
+use cw_house_staking::{
+  models::{
+    ClientLimits, ClientMetadata, ClientStatus, Config, DelegationType, FeeConfig, Operation,
+    PayoutWindowLimit,
+  },
+  msg::{
+    ClientResponse, ClientsResponse, ConfigResponse, DelegationsResponse, DelegatorsResponse,
+    ExecuteMsg, InstantiateMsg, InvariantViolation, QueryMsg,
+  },
+  state::{
+    GROWTH_DELEGATOR_COUNT, NET_GROWTH_DELEGATION, NET_PROFIT_DELEGATION, PROFIT_DELEGATOR_COUNT,
+  },
+};
+use cw_lib::models::{Owner, Token};
+use cw_multi_test::{App, Executor};
+
+fn setup() -> (App, Addr) {
+  let mut app = native_app();
+  let house = instantiate_house(
+    &mut app,
+    &InstantiateMsg {
+      owner: Owner::Address(Addr::unchecked(OWNER)),
+      token: Token::Native {
+        denom: DENOM.to_owned(),
+      },
+      fee: None,
+      config: None,
+    },
+  );
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::SetClient {
+        address: Addr::unchecked(CLIENT),
+        metadata: None,
+      },
+      &[],
+    )
+    .unwrap();
+  (app, house)
+}
+
+fn delegate(
+  app: &mut App,
+  house: &Addr,
+  delegator: &str,
+  growth: u128,
+  profit: u128,
+) {
+  app
+    .execute_contract(
+      Addr::unchecked(delegator),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: growth.into(),
+        profit: profit.into(),
+        mint_shares: None,
+        beneficiary: None,
+      },
+      &coins(growth + profit, DENOM),
+    )
+    .unwrap();
+}
+
+fn receive_payment(
+  app: &mut App,
+  house: &Addr,
+  amount: u128,
+) {
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: amount.into(),
+      },
+      &coins(amount, DENOM),
+    )
+    .unwrap();
+}
+
+/// Execute the message without funds, returning the error it fails with, if
+/// any.
+fn try_execute(
+  app: &mut App,
+  house: &Addr,
+  sender: &str,
+  msg: &ExecuteMsg,
+) -> Result<(), String> {
+  app
+    .execute_contract(Addr::unchecked(sender), house.clone(), msg, &[])
+    .map(|_| ())
+    .map_err(|err| err.root_cause().to_string())
+}
+
+/// Have the client pay CAROL, returning the error it fails with, if any.
+fn send_payment(
+  app: &mut App,
+  house: &Addr,
+  amount: u128,
+) -> Result<(), String> {
+  try_execute(
+    app,
+    house,
+    CLIENT,
+    &ExecuteMsg::SendPayment {
+      recipient: Addr::unchecked(CAROL),
+      amount: amount.into(),
+    },
+  )
+}
+
+#[test]
+fn multi_delegator_lifecycle() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  delegate(&mut app, &house, BOB, 300, 100);
+
+  // the client receives revenue, which is split between both pools
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(1000u128),
+      },
+      &coins(1000, DENOM),
+    )
+    .unwrap();
+
+  // the client pays out a win, which only the growth pool absorbs
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::SendPayment {
+        recipient: Addr::unchecked(CAROL),
+        amount: Uint128::from(450u128),
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(native_balance(&app, CAROL), 450);
+  assert_eq!(native_balance(&app, house.as_str()), 1550);
+
+  let resp = select(&app, &house, Some(ALICE));
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(1450u128)));
+  assert_eq!(resp.total_profit_claimable, Some(Uint128::from(100u128)));
+  assert_eq!(resp.snapshots.unwrap().len(), 0);
+
+  // both pools earned 1 per unit delegated, and the growth pool lost 0.5
+  let pools = resp.pools.unwrap();
+  assert_eq!(pools.growth, Uint128::from(900u128));
+  assert_eq!(pools.profit, Uint128::from(100u128));
+  assert_eq!(pools.indices.growth, Decimal256::one());
+  assert_eq!(pools.indices.loss, Decimal256::percent(50));
+  assert_eq!(pools.indices.profit, Decimal256::one());
+
+  let stats = resp.stats.unwrap();
+  assert_eq!(stats.n_delegation_accounts, 2);
+  assert_eq!(stats.n_client_accounts, 1);
+  assert_eq!(stats.n_snapshots, 0);
+
+  let account = resp.account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(600u128));
+  assert_eq!(account.profit_delegation, Uint128::zero());
+  assert_eq!(account.growth_claimable, Uint128::from(600u128));
+  assert_eq!(account.loss_claimable, Uint128::from(300u128));
+  assert_eq!(account.profit_claimable, Uint128::zero());
+
+  let account = select(&app, &house, Some(BOB)).account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(300u128));
+  assert_eq!(account.profit_delegation, Uint128::from(100u128));
+  assert_eq!(account.growth_claimable, Uint128::from(300u128));
+  assert_eq!(account.loss_claimable, Uint128::from(150u128));
+  assert_eq!(account.profit_claimable, Uint128::from(100u128));
+
+  let account = select(&app, &house, Some(CLIENT)).account.unwrap();
+  assert_eq!(account.revenue_generated, Uint128::from(1000u128));
+  assert_eq!(account.liquidity_spent, Uint128::from(450u128));
+
+  let resp: DelegatorsResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Delegators {
+        start_after: Some(Addr::unchecked(ALICE)),
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(resp.delegators.len(), 1);
+  assert_eq!(resp.delegators[0].account.owner, Addr::unchecked(BOB));
+  assert_eq!(resp.delegators[0].growth_claimable, Uint128::from(300u128));
+  assert_eq!(resp.delegators[0].loss_claimable, Uint128::from(150u128));
+  assert_eq!(resp.delegators[0].profit_claimable, Uint128::from(100u128));
+
+  let resp: ClientsResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Clients {
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(resp.clients.len(), 1);
+  assert_eq!(resp.clients[0].owner, Addr::unchecked(CLIENT));
+
+  let report = check_invariants(&app, &house);
+  assert_eq!(report.violations, vec![]);
+  assert_eq!(report.balance, Uint128::from(1550u128));
+  assert_eq!(report.liquidity_dust, Uint128::zero());
+
+  // bob takes his profit
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::SendProfit {},
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(native_balance(&app, BOB), INITIAL_BALANCE - 400 + 100);
+  assert_eq!(
+    select(&app, &house, None).total_profit_claimable,
+    Some(Uint128::zero())
+  );
+
+  // alice withdraws everything
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::Withdraw {
+        growth: None,
+        profit: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE - 600 + 900);
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(550u128)));
+  assert_eq!(resp.stats.unwrap().n_delegation_accounts, 1);
+
+  // bob withdraws part of his growth delegation, keeping his account open
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::Withdraw {
+        growth: Some(Uint128::from(100u128)),
+        profit: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(native_balance(&app, BOB), INITIAL_BALANCE - 400 + 100 + 100);
+
+  let resp = select(&app, &house, Some(BOB));
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(450u128)));
+  assert_eq!(resp.stats.unwrap().n_delegation_accounts, 1);
+
+  let account = resp.account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(200u128));
+  assert_eq!(account.profit_delegation, Uint128::from(100u128));
+  assert_eq!(account.growth_claimable, Uint128::from(300u128));
+  assert_eq!(account.loss_claimable, Uint128::from(150u128));
+
+  // bob withdraws the rest, leaving the house empty
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::Withdraw {
+        growth: None,
+        profit: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(
+    native_balance(&app, BOB),
+    INITIAL_BALANCE - 400 + 100 + 100 + 450
+  );
+  assert_eq!(native_balance(&app, CLIENT), INITIAL_BALANCE - 1000);
+  assert_eq!(native_balance(&app, house.as_str()), 0);
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.total_liquidity, Some(Uint128::zero()));
+  assert_eq!(resp.total_profit_claimable, Some(Uint128::zero()));
+  assert_eq!(resp.stats.unwrap().n_delegation_accounts, 0);
+}
+
+#[test]
+fn only_clients_can_send_payment() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::SendPayment {
+        recipient: Addr::unchecked(BOB),
+        amount: Uint128::from(1000u128),
+      },
+      &[],
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "NotAuthorized");
+  assert_eq!(native_balance(&app, house.as_str()), 1000);
+}
+
+#[test]
+fn suspend_and_remove_client() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  let set_status = |app: &mut App, status: ClientStatus| {
+    app
+      .execute_contract(
+        Addr::unchecked(OWNER),
+        house.clone(),
+        &ExecuteMsg::SetClientStatus {
+          address: Addr::unchecked(CLIENT),
+          status,
+        },
+        &[],
+      )
+      .unwrap();
+  };
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::Reserve {
+        amount: Uint128::from(300u128),
+        id: "round".to_owned(),
+      },
+      &[],
+    )
+    .unwrap();
+
+  set_status(&mut app, ClientStatus::Suspended);
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::SendPayment {
+        recipient: Addr::unchecked(BOB),
+        amount: Uint128::from(100u128),
+      },
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "ClientSuspended");
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(100u128),
+      },
+      &coins(100, DENOM),
+    )
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "ClientSuspended");
+
+  // suspended clients remain queryable
+  let resp: ClientResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Client {
+        address: Addr::unchecked(CLIENT),
+      },
+    )
+    .unwrap();
+  assert_eq!(resp.client.status, ClientStatus::Suspended);
+
+  set_status(&mut app, ClientStatus::Active);
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::SendPayment {
+        recipient: Addr::unchecked(BOB),
+        amount: Uint128::from(100u128),
+      },
+      &[],
+    )
+    .unwrap();
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::RemoveClient {
+        address: Addr::unchecked(CLIENT),
+      },
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "NotAuthorized");
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::RemoveClient {
+        address: Addr::unchecked(CLIENT),
+      },
+      &[],
+    )
+    .unwrap();
+
+  // the removed client's reservation is released
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.stats.unwrap().n_client_accounts, 0);
+  assert_eq!(resp.free_liquidity, Some(Uint128::from(900u128)));
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::SendPayment {
+        recipient: Addr::unchecked(BOB),
+        amount: Uint128::from(100u128),
+      },
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "NotAuthorized");
+}
+
+#[test]
+fn anyone_can_amortize() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  delegate(&mut app, &house, BOB, 300, 100);
+
+  app
+    .execute_contract(
+      Addr::unchecked(CAROL),
+      house.clone(),
+      &ExecuteMsg::Amortize { limit: Some(10) },
+      &[],
+    )
+    .unwrap();
+
+  let stats = select(&app, &house, None).stats.unwrap();
+  assert_eq!(stats.memoization_queue_len, 2);
+
+  // limits beyond the queue's length are capped rather than allocated for
+  app
+    .execute_contract(
+      Addr::unchecked(CAROL),
+      house.clone(),
+      &ExecuteMsg::Amortize {
+        limit: Some(u32::MAX),
+      },
+      &[],
+    )
+    .unwrap();
+
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::Withdraw {
+        growth: None,
+        profit: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  // withdrawn accounts leave the queue
+  let stats = select(&app, &house, None).stats.unwrap();
+  assert_eq!(stats.memoization_queue_len, 1);
+  assert_eq!(stats.n_delegation_accounts, 1);
+}
+
+#[test]
+fn transfer_account_moves_and_merges() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  delegate(&mut app, &house, BOB, 300, 100);
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(1000u128),
+      },
+      &coins(1000, DENOM),
+    )
+    .unwrap();
+
+  // bob already has an account, so alice's is merged into it
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::TransferAccount {
+        recipient: Addr::unchecked(BOB),
+      },
+      &[],
+    )
+    .unwrap();
+
+  let resp = select(&app, &house, Some(BOB));
+  assert_eq!(resp.stats.unwrap().n_delegation_accounts, 1);
+
+  let account = resp.account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(900u128));
+  assert_eq!(account.profit_delegation, Uint128::from(100u128));
+  assert_eq!(account.growth_claimable, Uint128::from(900u128));
+  assert_eq!(account.profit_claimable, Uint128::from(100u128));
+
+  // carol has no account, so bob's is moved as-is
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::TransferAccount {
+        recipient: Addr::unchecked(CAROL),
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(
+    select(&app, &house, Some(BOB))
+      .account
+      .unwrap()
+      .growth_delegation,
+    Uint128::zero()
+  );
+
+  app
+    .execute_contract(
+      Addr::unchecked(CAROL),
+      house.clone(),
+      &ExecuteMsg::Withdraw {
+        growth: None,
+        profit: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(native_balance(&app, CAROL), 2000);
+  assert_eq!(native_balance(&app, house.as_str()), 0);
+}
+
+#[test]
+fn config_limits_delegation() {
+  let (mut app, house) = setup();
+
+  let config = Config {
+    min_delegation: Uint128::from(100u128),
+    max_liquidity: Some(Uint128::from(1500u128)),
+    max_account_delegation: Some(Uint128::from(1000u128)),
+    snapshot_query_limit: 10,
+    amortization_batch_size: 2,
+    amortization_snapshot_limit: 10,
+  };
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::UpdateConfig {
+        config: config.clone(),
+      },
+      &[],
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "NotAuthorized");
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::UpdateConfig {
+        config: config.clone(),
+      },
+      &[],
+    )
+    .unwrap();
+
+  let resp: ConfigResponse = app
+    .wrap()
+    .query_wasm_smart(&house, &QueryMsg::Config {})
+    .unwrap();
+  assert_eq!(resp.config, config);
+
+  for (delegator, amount, error) in [
+    (ALICE, 50, "DelegationBelowMinimum"),
+    (ALICE, 1100, "AccountDelegationCapExceeded"),
+  ] {
+    let err = app
+      .execute_contract(
+        Addr::unchecked(delegator),
+        house.clone(),
+        &ExecuteMsg::Delegate {
+          growth: Uint128::from(amount),
+          profit: Uint128::zero(),
+          mint_shares: None,
+          beneficiary: None,
+        },
+        &coins(amount, DENOM),
+      )
+      .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), error);
+  }
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: Uint128::from(600u128),
+        profit: Uint128::zero(),
+        mint_shares: None,
+        beneficiary: None,
+      },
+      &coins(600, DENOM),
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "LiquidityCapExceeded");
+}
+
+#[test]
+fn payment_without_delegators() {
+  let (mut app, house) = setup();
+
+  // with nothing delegated, revenue goes entirely to liquidity instead of
+  // being split by a zero total delegation
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(500u128),
+      },
+      &coins(500, DENOM),
+    )
+    .unwrap();
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(500u128)));
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  let account = select(&app, &house, Some(ALICE)).account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(1000u128));
+  assert_eq!(account.growth_claimable, Uint128::zero());
+}
+
+#[test]
+fn settle_account_memoizes_claims() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(500u128),
+      },
+      &coins(500, DENOM),
+    )
+    .unwrap();
+
+  let resp = app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::SettleAccount { max_snapshots: 10 },
+      &[],
+    )
+    .unwrap();
+
+  // accounts without legacy records settle in full
+  let wasm = resp.events.iter().find(|e| e.ty == "wasm").unwrap();
+  assert!(wasm
+    .attributes
+    .iter()
+    .any(|a| a.key == "is_settled" && a.value == "true"));
+
+  let account = select(&app, &house, Some(ALICE)).account.unwrap();
+  assert_eq!(account.growth_claimable, Uint128::from(500u128));
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(CAROL),
+      house.clone(),
+      &ExecuteMsg::SettleAccount { max_snapshots: 10 },
+      &[],
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "NotFound");
+}
+
+#[test]
+fn update_client_metadata() {
+  let (mut app, house) = setup();
+
+  let metadata = ClientMetadata {
+    name: Some("Dice".to_owned()),
+    description: Some("Roll under to win".to_owned()),
+    url: Some("https://example.com/dice".to_owned()),
+    category: Some("dice".to_owned()),
+    logo: None,
+  };
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::UpdateClientMetadata {
+        address: Addr::unchecked(CLIENT),
+        metadata: metadata.clone(),
+      },
+      &[],
+    )
+    .unwrap();
+
+  let resp: ClientResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Client {
+        address: Addr::unchecked(CLIENT),
+      },
+    )
+    .unwrap();
+  assert_eq!(resp.client.metadata, metadata);
+
+  let resp: ClientsResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Clients {
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(resp.clients[0].metadata, metadata);
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::UpdateClientMetadata {
+        address: Addr::unchecked(CLIENT),
+        metadata: ClientMetadata {
+          name: Some("x".repeat(1000)),
+          ..metadata
+        },
+      },
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "InvalidClientMetadata");
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::UpdateClientMetadata {
+        address: Addr::unchecked(BOB),
+        metadata: ClientMetadata::default(),
+      },
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "NotFound");
+}
+
+#[test]
+fn config_limits_compound_and_merge() {
+  let (mut app, house) = setup();
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::UpdateConfig {
+        config: Config {
+          max_liquidity: Some(Uint128::from(1000u128)),
+          max_account_delegation: Some(Uint128::from(900u128)),
+          ..Config::default()
+        },
+      },
+      &[],
+    )
+    .unwrap();
+
+  // with nothing in the growth pool, all revenue becomes profit
+  delegate(&mut app, &house, ALICE, 0, 800);
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(400u128),
+      },
+      &coins(400, DENOM),
+    )
+    .unwrap();
+
+  for (amount, error) in [
+    (400u128, "LiquidityCapExceeded"),
+    (150u128, "AccountDelegationCapExceeded"),
+  ] {
+    let err = app
+      .execute_contract(
+        Addr::unchecked(ALICE),
+        house.clone(),
+        &ExecuteMsg::Compound {
+          pool: DelegationType::Growth,
+          amount: Some(Uint128::from(amount)),
+        },
+        &[],
+      )
+      .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), error);
+  }
+
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::Compound {
+        pool: DelegationType::Growth,
+        amount: Some(Uint128::from(100u128)),
+      },
+      &[],
+    )
+    .unwrap();
+
+  // merging bob's account would take alice past the per-account cap
+  delegate(&mut app, &house, BOB, 0, 100);
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::TransferAccount {
+        recipient: Addr::unchecked(ALICE),
+      },
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "AccountDelegationCapExceeded");
+
+  let account = select(&app, &house, Some(ALICE)).account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(100u128));
+  assert_eq!(account.profit_delegation, Uint128::from(800u128));
+}
+
+#[test]
+fn delegations_include_account() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(500u128),
+      },
+      &coins(500, DENOM),
+    )
+    .unwrap();
+
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::SettleAccount { max_snapshots: 10 },
+      &[],
+    )
+    .unwrap();
+
+  // accounts created under index accounting have no legacy records
+  let resp: DelegationsResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Delegations {
+        wallet: Addr::unchecked(ALICE),
+        pool: DelegationType::Growth,
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+
+  assert!(resp.delegations.is_empty());
+
+  let account = resp.account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(1000u128));
+  assert_eq!(account.memoized_growth, Uint128::from(500u128));
+  assert_eq!(account.indices.growth, Decimal256::percent(50));
+
+  let resp: DelegationsResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Delegations {
+        wallet: Addr::unchecked(BOB),
+        pool: DelegationType::Growth,
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+
+  assert_eq!(resp.account, None);
+}
+
+#[test]
+fn client_limits_cap_payouts() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  let set_limits_msg = ExecuteMsg::SetClientLimits {
+    address: Addr::unchecked(CLIENT),
+    limits: Some(ClientLimits {
+      max_payout: Some(Uint128::from(200u128)),
+      max_window_payout: Some(PayoutWindowLimit {
+        amount: Uint128::from(300u128),
+        seconds: 60,
+      }),
+      max_net_loss: Some(Uint128::from(400u128)),
+    }),
+  };
+
+  let err = app
+    .execute_contract(Addr::unchecked(ALICE), house.clone(), &set_limits_msg, &[])
+    .unwrap_err();
+  assert_eq!(err.root_cause().to_string(), "NotAuthorized");
+
+  app
+    .execute_contract(Addr::unchecked(OWNER), house.clone(), &set_limits_msg, &[])
+    .unwrap();
+
+  // a single payment can't exceed the max payout
+  assert_eq!(
+    send_payment(&mut app, &house, 250),
+    Err("PayoutLimitExceeded".to_owned())
+  );
+
+  // nor can the payments made within a window exceed its limit
+  send_payment(&mut app, &house, 200).unwrap();
+  assert_eq!(
+    send_payment(&mut app, &house, 150),
+    Err("WindowPayoutLimitExceeded".to_owned())
+  );
+  send_payment(&mut app, &house, 100).unwrap();
+
+  // once the window has elapsed, the next payment starts a new one
+  app.update_block(|block| block.time = block.time.plus_seconds(60));
+  send_payment(&mut app, &house, 100).unwrap();
+
+  let resp: ClientResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Client {
+        address: Addr::unchecked(CLIENT),
+      },
+    )
+    .unwrap();
+  let window = resp.client.payout_window.unwrap();
+  assert_eq!(window.started_at, app.block_info().time);
+  assert_eq!(window.amount_spent, Uint128::from(100u128));
+  assert_eq!(resp.client.amount_spent, Uint128::from(400u128));
+
+  // the client has lost 400 in total, which is all it can lose
+  assert_eq!(
+    send_payment(&mut app, &house, 1),
+    Err("NetLossLimitExceeded".to_owned())
+  );
+
+  // revenue it brings in lowers its net loss again
+  receive_payment(&mut app, &house, 100);
+  send_payment(&mut app, &house, 100).unwrap();
+  assert_eq!(
+    send_payment(&mut app, &house, 1),
+    Err("NetLossLimitExceeded".to_owned())
+  );
+
+  assert_eq!(native_balance(&app, CAROL), 500);
+}
+
+#[test]
+fn reserve_release_and_settle() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  let reserve = |amount: u128, id: &str| ExecuteMsg::Reserve {
+    amount: amount.into(),
+    id: id.to_owned(),
+  };
+  let release = |id: &str| ExecuteMsg::Release { id: id.to_owned() };
+  let settle = |payout: u128, id: &str| ExecuteMsg::Settle {
+    id: id.to_owned(),
+    payout: payout.into(),
+    recipient: Addr::unchecked(CAROL),
+  };
+  let withdraw = ExecuteMsg::Withdraw {
+    growth: None,
+    profit: None,
+  };
+
+  try_execute(&mut app, &house, CLIENT, &reserve(800, "round-1")).unwrap();
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.reserved_liquidity, Some(Uint128::from(800u128)));
+  assert_eq!(resp.free_liquidity, Some(Uint128::from(200u128)));
+
+  // ids are unique per client, and only free liquidity can be reserved
+  assert_eq!(
+    try_execute(&mut app, &house, CLIENT, &reserve(100, "round-1")),
+    Err("ReservationExists".to_owned())
+  );
+  assert_eq!(
+    try_execute(&mut app, &house, CLIENT, &reserve(300, "round-2")),
+    Err("InsufficientLiquidity".to_owned())
+  );
+
+  // nor can reserved liquidity be paid out or withdrawn
+  assert_eq!(
+    send_payment(&mut app, &house, 300),
+    Err("InsufficientLiquidity".to_owned())
+  );
+  assert_eq!(
+    try_execute(&mut app, &house, ALICE, &withdraw),
+    Err("InsufficientLiquidity".to_owned())
+  );
+
+  // settling pays out up to the reserved amount and frees the rest
+  assert_eq!(
+    try_execute(&mut app, &house, CLIENT, &settle(900, "round-1")),
+    Err("PayoutExceedsReservation".to_owned())
+  );
+  try_execute(&mut app, &house, CLIENT, &settle(500, "round-1")).unwrap();
+  assert_eq!(native_balance(&app, CAROL), 500);
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(500u128)));
+  assert_eq!(resp.reserved_liquidity, Some(Uint128::zero()));
+  assert_eq!(resp.free_liquidity, Some(Uint128::from(500u128)));
+
+  for id in ["round-1", "unknown"] {
+    assert_eq!(
+      try_execute(&mut app, &house, CLIENT, &settle(0, id)),
+      Err("NotFound".to_owned())
+    );
+  }
+
+  // releasing frees the whole reservation without paying anything out
+  try_execute(&mut app, &house, CLIENT, &reserve(300, "round-2")).unwrap();
+  try_execute(&mut app, &house, CLIENT, &release("round-2")).unwrap();
+  assert_eq!(
+    try_execute(&mut app, &house, CLIENT, &release("round-2")),
+    Err("NotFound".to_owned())
+  );
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.reserved_liquidity, Some(Uint128::zero()));
+  assert_eq!(resp.free_liquidity, Some(Uint128::from(500u128)));
+  assert_eq!(native_balance(&app, CAROL), 500);
+
+  try_execute(&mut app, &house, ALICE, &withdraw).unwrap();
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE - 500);
+}
+
+#[test]
+fn unbonding_absorbs_losses_until_claimed() {
+  let (mut app, house) = setup();
+
+  try_execute(
+    &mut app,
+    &house,
+    OWNER,
+    &ExecuteMsg::SetUnbondingPeriod { seconds: 100 },
+  )
+  .unwrap();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  delegate(&mut app, &house, BOB, 400, 0);
+
+  try_execute(
+    &mut app,
+    &house,
+    CLIENT,
+    &ExecuteMsg::Reserve {
+      amount: Uint128::from(900u128),
+      id: "round".to_owned(),
+    },
+  )
+  .unwrap();
+
+  // nothing leaves the house yet, so the reservation doesn't get in the way
+  try_execute(
+    &mut app,
+    &house,
+    ALICE,
+    &ExecuteMsg::Withdraw {
+      growth: None,
+      profit: None,
+    },
+  )
+  .unwrap();
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE - 600);
+
+  let created_at = app.block_info().time;
+  let unbondings = select(&app, &house, Some(ALICE))
+    .account
+    .unwrap()
+    .unbondings;
+  assert_eq!(unbondings.len(), 1);
+  assert_eq!(unbondings[0].amount, Uint128::from(600u128));
+  assert_eq!(unbondings[0].matures_at, created_at.plus_seconds(100));
+
+  // immature unbondings aren't claimed
+  try_execute(&mut app, &house, ALICE, &ExecuteMsg::ClaimUnbonded {}).unwrap();
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE - 600);
+
+  // matured ones can't be claimed while the liquidity is still reserved
+  app.update_block(|block| block.time = block.time.plus_seconds(100));
+  assert_eq!(
+    try_execute(&mut app, &house, ALICE, &ExecuteMsg::ClaimUnbonded {}),
+    Err("InsufficientLiquidity".to_owned())
+  );
+
+  // the unbonding absorbs 600 / 1000 of the round's loss, and BOB the rest
+  try_execute(
+    &mut app,
+    &house,
+    CLIENT,
+    &ExecuteMsg::Settle {
+      id: "round".to_owned(),
+      payout: Uint128::from(400u128),
+      recipient: Addr::unchecked(CAROL),
+    },
+  )
+  .unwrap();
+
+  let unbondings = select(&app, &house, Some(ALICE))
+    .account
+    .unwrap()
+    .unbondings;
+  assert_eq!(unbondings[0].amount, Uint128::from(360u128));
+
+  let account = select(&app, &house, Some(BOB)).account.unwrap();
+  assert_eq!(account.loss_claimable, Uint128::from(160u128));
+
+  try_execute(&mut app, &house, ALICE, &ExecuteMsg::ClaimUnbonded {}).unwrap();
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE - 600 + 360);
+
+  let resp = select(&app, &house, Some(ALICE));
+  assert_eq!(resp.account.unwrap().unbondings, vec![]);
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(240u128)));
+
+  let report = check_invariants(&app, &house);
+  assert_eq!(report.violations, vec![]);
+  assert_eq!(report.unbonding_amount, Uint128::zero());
+}
+
+#[test]
+fn unbonding_epoch_resets_after_wipeout() {
+  let (mut app, house) = setup();
+
+  try_execute(
+    &mut app,
+    &house,
+    OWNER,
+    &ExecuteMsg::SetUnbondingPeriod { seconds: 100 },
+  )
+  .unwrap();
+
+  let withdraw = ExecuteMsg::Withdraw {
+    growth: None,
+    profit: None,
+  };
+
+  delegate(&mut app, &house, ALICE, 200, 0);
+  delegate(&mut app, &house, BOB, 0, 300);
+
+  try_execute(&mut app, &house, ALICE, &withdraw).unwrap();
+
+  // with nothing left in the growth pool, the unbonding absorbs the whole loss
+  send_payment(&mut app, &house, 200).unwrap();
+
+  let unbondings = select(&app, &house, Some(ALICE))
+    .account
+    .unwrap()
+    .unbondings;
+  assert_eq!(unbondings[0].amount, Uint128::zero());
+
+  // the next unbonding starts a new epoch rather than buying worthless shares
+  delegate(&mut app, &house, ALICE, 100, 0);
+  try_execute(&mut app, &house, ALICE, &withdraw).unwrap();
+
+  let unbondings = select(&app, &house, Some(ALICE))
+    .account
+    .unwrap()
+    .unbondings;
+  assert_eq!(
+    unbondings
+      .iter()
+      .map(|u| u.amount.u128())
+      .collect::<Vec<_>>(),
+    vec![0, 100]
+  );
+
+  app.update_block(|block| block.time = block.time.plus_seconds(100));
+  try_execute(&mut app, &house, ALICE, &ExecuteMsg::ClaimUnbonded {}).unwrap();
+
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE - 200);
+  assert_eq!(
+    select(&app, &house, Some(ALICE))
+      .account
+      .unwrap()
+      .unbondings,
+    vec![]
+  );
+
+  let report = check_invariants(&app, &house);
+  assert_eq!(report.violations, vec![]);
+  assert_eq!(report.net_liquidity, Uint128::from(300u128));
+}
+
+#[test]
+fn uneven_losses_round_up() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  delegate(&mut app, &house, BOB, 300, 0);
+
+  // 451 doesn't split evenly, so each account is charged the extra fraction
+  send_payment(&mut app, &house, 451).unwrap();
+
+  let alice = select(&app, &house, Some(ALICE)).account.unwrap();
+  assert_eq!(alice.loss_claimable, Uint128::from(301u128));
+
+  let bob = select(&app, &house, Some(BOB)).account.unwrap();
+  assert_eq!(bob.loss_claimable, Uint128::from(151u128));
+
+  // which leaves the house holding more than it owes, never less
+  let report = check_invariants(&app, &house);
+  assert_eq!(report.violations, vec![]);
+  assert_eq!(report.net_liquidity, Uint128::from(449u128));
+  assert_eq!(report.liquidity_dust, Uint128::from(1u128));
+}
+
+#[test]
+fn rebalance_moves_delegation_between_pools() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  delegate(&mut app, &house, BOB, 300, 100);
+  receive_payment(&mut app, &house, 1000);
+
+  // growth and profit delegation and the number of delegators in each pool
+  let pools = |app: &App| {
+    let querier = app.wrap();
+    (
+      NET_GROWTH_DELEGATION
+        .query(&querier, house.clone())
+        .unwrap()
+        .u128(),
+      NET_PROFIT_DELEGATION
+        .query(&querier, house.clone())
+        .unwrap()
+        .u128(),
+      GROWTH_DELEGATOR_COUNT
+        .query(&querier, house.clone())
+        .unwrap(),
+      PROFIT_DELEGATOR_COUNT
+        .query(&querier, house.clone())
+        .unwrap(),
+    )
+  };
+
+  assert_eq!(pools(&app), (900, 100, 2, 1));
+
+  // moving all of ALICE's growth delegation takes her out of the growth pool
+  try_execute(
+    &mut app,
+    &house,
+    ALICE,
+    &ExecuteMsg::Rebalance {
+      growth_to_profit: Some(Uint128::from(600u128)),
+      profit_to_growth: None,
+    },
+  )
+  .unwrap();
+  assert_eq!(pools(&app), (300, 700, 1, 2));
+
+  // and moving all of BOB's profit delegation takes him out of the profit pool
+  try_execute(
+    &mut app,
+    &house,
+    BOB,
+    &ExecuteMsg::Rebalance {
+      growth_to_profit: None,
+      profit_to_growth: Some(Uint128::from(100u128)),
+    },
+  )
+  .unwrap();
+  assert_eq!(pools(&app), (400, 600, 1, 1));
+
+  assert_eq!(
+    try_execute(
+      &mut app,
+      &house,
+      ALICE,
+      &ExecuteMsg::Rebalance {
+        growth_to_profit: Some(Uint128::one()),
+        profit_to_growth: None,
+      },
+    ),
+    Err("InsufficientDelegation".to_owned())
+  );
+
+  // claims settled before rebalancing are kept, and later revenue is earned
+  // in the new pools
+  receive_payment(&mut app, &house, 1000);
+
+  let account = select(&app, &house, Some(ALICE)).account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::zero());
+  assert_eq!(account.profit_delegation, Uint128::from(600u128));
+  assert_eq!(account.growth_claimable, Uint128::from(600u128));
+  assert_eq!(account.profit_claimable, Uint128::from(600u128));
+
+  let account = select(&app, &house, Some(BOB)).account.unwrap();
+  assert_eq!(account.growth_delegation, Uint128::from(400u128));
+  assert_eq!(account.profit_delegation, Uint128::zero());
+  assert_eq!(account.growth_claimable, Uint128::from(700u128));
+  assert_eq!(account.profit_claimable, Uint128::from(100u128));
+
+  assert_eq!(check_invariants(&app, &house).violations, vec![]);
+}
+
+#[test]
+fn fees_come_off_the_top_of_revenue() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  delegate(&mut app, &house, BOB, 300, 100);
+
+  let set_fee_config = |rate: u16, recipient: Option<&str>| ExecuteMsg::SetFeeConfig {
+    fee: FeeConfig {
+      rate,
+      recipient: recipient.map(Addr::unchecked),
+    },
+  };
+
+  assert_eq!(
+    try_execute(&mut app, &house, OWNER, &set_fee_config(10_001, None)),
+    Err("InvalidFee".to_owned())
+  );
+  try_execute(&mut app, &house, OWNER, &set_fee_config(1000, None)).unwrap();
+
+  // a 10% fee is taken before the rest is split between the pools
+  receive_payment(&mut app, &house, 1000);
+
+  let resp = select(&app, &house, Some(BOB));
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(1810u128)));
+  assert_eq!(resp.total_profit_claimable, Some(Uint128::from(90u128)));
+
+  let stats = resp.stats.unwrap();
+  assert_eq!(stats.lifetime_fees, Uint128::from(100u128));
+  assert_eq!(stats.fees_claimable, Uint128::from(100u128));
+
+  let account = resp.account.unwrap();
+  assert_eq!(account.growth_claimable, Uint128::from(270u128));
+  assert_eq!(account.profit_claimable, Uint128::from(90u128));
+
+  assert_eq!(
+    try_execute(&mut app, &house, ALICE, &ExecuteMsg::ClaimFees {}),
+    Err("NotAuthorized".to_owned())
+  );
+
+  // without a recipient, fees are sent to the claimant
+  try_execute(&mut app, &house, OWNER, &ExecuteMsg::ClaimFees {}).unwrap();
+  assert_eq!(native_balance(&app, OWNER), 100);
+
+  // fees round down, and are sent to the recipient once one is set
+  try_execute(&mut app, &house, OWNER, &set_fee_config(1000, Some(CAROL))).unwrap();
+  receive_payment(&mut app, &house, 1011);
+  try_execute(&mut app, &house, OWNER, &ExecuteMsg::ClaimFees {}).unwrap();
+  assert_eq!(native_balance(&app, CAROL), 101);
+  assert_eq!(native_balance(&app, OWNER), 100);
+
+  let stats = select(&app, &house, None).stats.unwrap();
+  assert_eq!(stats.lifetime_fees, Uint128::from(201u128));
+  assert_eq!(stats.fees_claimable, Uint128::zero());
+
+  assert_eq!(check_invariants(&app, &house).violations, vec![]);
+}
+
+#[test]
+fn release_while_send_payment_paused() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 1000, 0);
+
+  try_execute(
+    &mut app,
+    &house,
+    CLIENT,
+    &ExecuteMsg::Reserve {
+      amount: Uint128::from(800u128),
+      id: "round".to_owned(),
+    },
+  )
+  .unwrap();
+
+  try_execute(
+    &mut app,
+    &house,
+    OWNER,
+    &ExecuteMsg::Pause {
+      operations: vec![Operation::SendPayment],
+    },
+  )
+  .unwrap();
+
+  assert_eq!(
+    try_execute(
+      &mut app,
+      &house,
+      CLIENT,
+      &ExecuteMsg::Settle {
+        id: "round".to_owned(),
+        payout: Uint128::from(100u128),
+        recipient: Addr::unchecked(CAROL),
+      },
+    ),
+    Err("Paused".to_owned())
+  );
+
+  // releasing pays nothing out, so it frees the liquidity for withdrawals
+  try_execute(
+    &mut app,
+    &house,
+    CLIENT,
+    &ExecuteMsg::Release {
+      id: "round".to_owned(),
+    },
+  )
+  .unwrap();
+
+  try_execute(
+    &mut app,
+    &house,
+    ALICE,
+    &ExecuteMsg::Withdraw {
+      growth: None,
+      profit: None,
+    },
+  )
+  .unwrap();
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE);
+}
+
+#[test]
+fn invariants_flag_missing_balance() {
+  let (mut app, house) = setup();
+
+  delegate(&mut app, &house, ALICE, 600, 0);
+  assert_eq!(check_invariants(&app, &house).violations, vec![]);
+
+  // funds leaving the house outside of its own accounting
+  app.init_modules(|router, _, storage| {
+    router
+      .bank
+      .init_balance(storage, &house, coins(500, DENOM))
+      .unwrap();
+  });
+
+  let report = check_invariants(&app, &house);
+  assert_eq!(report.balance, Uint128::from(500u128));
+  assert_eq!(report.expected_balance, Uint128::from(600u128));
+  assert_eq!(
+    report.violations,
+    vec![InvariantViolation {
+      name: "balance".to_owned(),
+      expected: Uint128::from(600u128),
+      actual: Uint128::from(500u128),
+    }]
+  );
+}
@@ -0,0 +1,294 @@
+mod common;
+
+use common::*;
+use cosmwasm_std::{coins, to_binary, Addr, Uint128};
+use cw20::Cw20ExecuteMsg;
+use cw_house_staking::{
+  models::{Config, ShareTokenConfig},
+  msg::{DelegatorsResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg},
+};
+use cw_lib::models::{Owner, Token};
+use cw_multi_test::{App, Executor};
+
+fn setup() -> (App, Addr, Addr) {
+  let mut app = native_app();
+  let house = instantiate_house(
+    &mut app,
+    &InstantiateMsg {
+      owner: Owner::Address(Addr::unchecked(OWNER)),
+      token: Token::Native {
+        denom: DENOM.to_owned(),
+      },
+      fee: None,
+      config: None,
+    },
+  );
+  let cw20_code_id = app.store_code(cw20_contract());
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::CreateShareToken {
+        config: ShareTokenConfig {
+          code_id: cw20_code_id,
+          name: "House Shares".to_owned(),
+          symbol: "HOUSE".to_owned(),
+          decimals: 6,
+        },
+      },
+      &[],
+    )
+    .unwrap();
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::SetClient {
+        address: Addr::unchecked(CLIENT),
+        metadata: None,
+      },
+      &[],
+    )
+    .unwrap();
+  let share_token = select(&app, &house, None).shares.unwrap().token;
+  (app, house, share_token)
+}
+
+fn mint_shares(
+  app: &mut App,
+  house: &Addr,
+  delegator: &str,
+  amount: u128,
+) {
+  app
+    .execute_contract(
+      Addr::unchecked(delegator),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: amount.into(),
+        profit: Uint128::zero(),
+        mint_shares: Some(true),
+        beneficiary: None,
+      },
+      &coins(amount, DENOM),
+    )
+    .unwrap();
+}
+
+#[test]
+fn shares_track_vault_value() {
+  let (mut app, house, share_token) = setup();
+
+  mint_shares(&mut app, &house, ALICE, 1000);
+  assert_eq!(cw20_balance(&app, &share_token, ALICE), 1000);
+
+  // revenue grows the vault, raising the share price
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(200u128),
+      },
+      &coins(200, DENOM),
+    )
+    .unwrap();
+
+  mint_shares(&mut app, &house, BOB, 600);
+  assert_eq!(cw20_balance(&app, &share_token, BOB), 500);
+
+  let shares = select(&app, &house, None).shares.unwrap();
+  assert_eq!(shares.supply, Uint128::from(1500u128));
+  assert_eq!(shares.vault_value, Uint128::from(1800u128));
+
+  // alice redeems all of her shares through the receive hook
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      share_token.clone(),
+      &Cw20ExecuteMsg::Send {
+        contract: house.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&ReceiveMsg::Redeem {}).unwrap(),
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(cw20_balance(&app, &share_token, ALICE), 0);
+  assert_eq!(cw20_balance(&app, &share_token, house.as_str()), 0);
+  assert_eq!(native_balance(&app, ALICE), INITIAL_BALANCE - 1000 + 1200);
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(600u128)));
+
+  let shares = resp.shares.unwrap();
+  assert_eq!(shares.supply, Uint128::from(500u128));
+  assert_eq!(shares.vault_value, Uint128::from(600u128));
+}
+
+#[test]
+fn shares_only_cover_growth_pool() {
+  let (mut app, house, _) = setup();
+
+  let err = app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: Uint128::from(100u128),
+        profit: Uint128::from(100u128),
+        mint_shares: Some(true),
+        beneficiary: None,
+      },
+      &coins(200, DENOM),
+    )
+    .unwrap_err();
+
+  assert_eq!(err.root_cause().to_string(), "InvalidAmount");
+}
+
+#[test]
+fn last_delegator_withdraws_while_shares_exist() {
+  let (mut app, house, _) = setup();
+
+  mint_shares(&mut app, &house, ALICE, 600);
+
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: Uint128::from(300u128),
+        profit: Uint128::from(100u128),
+        mint_shares: None,
+        beneficiary: None,
+      },
+      &coins(400, DENOM),
+    )
+    .unwrap();
+
+  // the vault isn't counted or listed as a delegation account
+  let stats = select(&app, &house, None).stats.unwrap();
+  assert_eq!(stats.n_delegation_accounts, 1);
+  assert_eq!(stats.memoization_queue_len, 1);
+
+  let resp: DelegatorsResponse = app
+    .wrap()
+    .query_wasm_smart(
+      &house,
+      &QueryMsg::Delegators {
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(resp.delegators.len(), 1);
+  assert_eq!(resp.delegators[0].account.owner, Addr::unchecked(BOB));
+
+  app
+    .execute_contract(
+      Addr::unchecked(CLIENT),
+      house.clone(),
+      &ExecuteMsg::ReceivePayment {
+        sender: None,
+        amount: Uint128::from(500u128),
+      },
+      &coins(500, DENOM),
+    )
+    .unwrap();
+
+  app
+    .execute_contract(
+      Addr::unchecked(BOB),
+      house.clone(),
+      &ExecuteMsg::Withdraw {
+        growth: None,
+        profit: None,
+      },
+      &[],
+    )
+    .unwrap();
+
+  // bob takes his delegation, growth and profit, but not the liquidity still
+  // backing the vault's shares
+  assert_eq!(native_balance(&app, BOB), INITIAL_BALANCE - 400 + 600);
+
+  let resp = select(&app, &house, None);
+  assert_eq!(resp.stats.unwrap().n_delegation_accounts, 0);
+  assert_eq!(resp.total_liquidity, Some(Uint128::from(900u128)));
+  assert_eq!(resp.total_profit_claimable, Some(Uint128::zero()));
+  assert_eq!(resp.shares.unwrap().vault_value, Uint128::from(900u128));
+
+  let report = check_invariants(&app, &house);
+  assert_eq!(report.violations, vec![]);
+}
+
+#[test]
+fn minting_counts_toward_account_cap() {
+  let (mut app, house, share_token) = setup();
+
+  app
+    .execute_contract(
+      Addr::unchecked(OWNER),
+      house.clone(),
+      &ExecuteMsg::UpdateConfig {
+        config: Config {
+          max_account_delegation: Some(Uint128::from(1000u128)),
+          ..Config::default()
+        },
+      },
+      &[],
+    )
+    .unwrap();
+
+  let mint_for = |app: &mut App, delegator: &str, beneficiary: &str, amount: u128| {
+    app
+      .execute_contract(
+        Addr::unchecked(delegator),
+        house.clone(),
+        &ExecuteMsg::Delegate {
+          growth: amount.into(),
+          profit: Uint128::zero(),
+          mint_shares: Some(true),
+          beneficiary: Some(Addr::unchecked(beneficiary)),
+        },
+        &coins(amount, DENOM),
+      )
+      .map(|_| ())
+      .map_err(|err| err.root_cause().to_string())
+  };
+
+  app
+    .execute_contract(
+      Addr::unchecked(ALICE),
+      house.clone(),
+      &ExecuteMsg::Delegate {
+        growth: Uint128::from(400u128),
+        profit: Uint128::zero(),
+        mint_shares: None,
+        beneficiary: None,
+      },
+      &coins(400, DENOM),
+    )
+    .unwrap();
+  mint_shares(&mut app, &house, ALICE, 500);
+
+  // the cap covers alice's account together with the shares she holds
+  assert_eq!(
+    mint_for(&mut app, ALICE, ALICE, 200),
+    Err("AccountDelegationCapExceeded".to_owned())
+  );
+  mint_for(&mut app, ALICE, ALICE, 100).unwrap();
+
+  // shares minted for her by someone else count toward her cap, not theirs
+  assert_eq!(
+    mint_for(&mut app, BOB, ALICE, 100),
+    Err("AccountDelegationCapExceeded".to_owned())
+  );
+  mint_for(&mut app, BOB, BOB, 1000).unwrap();
+
+  assert_eq!(cw20_balance(&app, &share_token, ALICE), 600);
+  assert_eq!(cw20_balance(&app, &share_token, BOB), 1000);
+}